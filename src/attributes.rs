@@ -103,7 +103,6 @@ pub unsafe fn get_str_attribute_slice(handle: *mut Ihandle, name: &str) -> Cow<s
     }
 }
 
-#[cfg(for_future_use)] // silence dead_code warning (probably) the best way
 pub fn get_attribute_handle(ih: *mut Ihandle, name: &str) -> *mut Ihandle {
     unsafe {
         let mut name_buf = SmallVec::<[u8; 64]>::new();
@@ -126,6 +125,50 @@ pub fn get_int_int_attribute(handle: *mut Ihandle, name: &str) -> (i32, i32) {
     }
 }
 
+// Used for attributes like "BGCOLOR2:3" that take a line and column, such as the matrix
+// control's cell attributes. IUP's matrix uses an empty name for a cell's value.
+#[cfg_attr(not(feature = "iup-controls"), allow(dead_code))]
+pub fn set_str_attribute_id2(handle: *mut Ihandle, name: &str, lin: i32, col: i32, value: &str) {
+    unsafe {
+        let mut name_buf = SmallVec::<[u8; 64]>::new();
+        let c_name = str_to_c_vec(name, &mut name_buf);
+        let mut value_buf = SmallVec::<[u8; 64]>::new();
+        let c_value = str_to_c_vec(value, &mut value_buf);
+        IupSetStrAttributeId2(handle, c_name, lin as c_int, col as c_int, c_value);
+    }
+}
+
+#[cfg_attr(not(feature = "iup-controls"), allow(dead_code))]
+pub fn get_attribute_ptr_id2(handle: *mut Ihandle, name: &str, lin: i32, col: i32) -> *mut c_char {
+    unsafe {
+        let mut name_buf = SmallVec::<[u8; 64]>::new();
+        let c_name = str_to_c_vec(name, &mut name_buf);
+        IupGetAttributeId2(handle, c_name, lin as c_int, col as c_int)
+    }
+}
+
+#[cfg_attr(not(feature = "iup-controls"), allow(dead_code))]
+pub fn reset_attribute_id2(handle: *mut Ihandle, name: &str, lin: i32, col: i32) {
+    unsafe {
+        let mut name_buf = SmallVec::<[u8; 64]>::new();
+        let c_name = str_to_c_vec(name, &mut name_buf);
+        IupSetAttributeId2(handle, c_name, lin as c_int, col as c_int, ::std::ptr::null());
+    }
+}
+
+// See `get_str_attribute` for why the value is copied.
+#[cfg_attr(not(feature = "iup-controls"), allow(dead_code))]
+pub fn get_str_attribute_id2(handle: *mut Ihandle, name: &str, lin: i32, col: i32) -> String {
+    unsafe {
+        let value = get_attribute_ptr_id2(handle, name, lin, col);
+        if value.is_null() {
+            String::new()
+        } else {
+            CStr::from_ptr(value).to_string_lossy().into_owned()
+        }
+    }
+}
+
 thread_local!(static UNIQUE_ATTRIBUTE_NAME_COUNTER: Cell<u32> = Cell::new(0));
 
 fn get_unique_attribute_name() -> String {
@@ -323,6 +366,78 @@ pub trait CanFocusAttribute : Control {
     }
 }
 
+pub trait DropFilesTargetAttribute : Control {
+    fn drop_files_target(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "DROPFILESTARGET\0") == "YES"
+        }
+    }
+
+    /// Sets whether files can be dropped on the control from the file manager. The dropped files
+    /// are delivered to `drop_files_event`.
+    fn set_drop_files_target(&self, target: bool) -> &Self {
+        set_str_attribute(self.handle(), "DROPFILESTARGET\0", if target { "YES\0" } else { "NO\0" });
+        self
+    }
+}
+
+// IUP requires the types to be set before drag and drop is enabled, so the types and the
+// DRAGSOURCE and DROPTARGET attributes are always set together.
+pub trait DragDropAttribute : Control {
+    fn drag_source(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "DRAGSOURCE\0") == "YES"
+        }
+    }
+
+    /// Makes the control a source for dragging data of the specified types, such as `"TEXT"` or
+    /// an application defined name. An empty slice stops the control being a drag source.
+    ///
+    /// The data is provided by `drag_data_event`.
+    fn set_drag_source(&self, types: &[&str]) -> &Self {
+        if types.is_empty() {
+            set_str_attribute(self.handle(), "DRAGSOURCE\0", "NO\0");
+        } else {
+            set_str_attribute(self.handle(), "DRAGTYPES\0", &format!("{}\0", types.join(",")));
+            set_str_attribute(self.handle(), "DRAGSOURCE\0", "YES\0");
+        }
+        self
+    }
+
+    fn drag_source_move(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "DRAGSOURCEMOVE\0") == "YES"
+        }
+    }
+
+    /// Sets whether a drag can move the data instead of only copying it. Whether the data was
+    /// moved is passed to `drag_end_event`.
+    fn set_drag_source_move(&self, allow_move: bool) -> &Self {
+        set_str_attribute(self.handle(), "DRAGSOURCEMOVE\0", if allow_move { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    fn drop_target(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "DROPTARGET\0") == "YES"
+        }
+    }
+
+    /// Makes the control accept dropped data of the specified types. An empty slice stops the
+    /// control being a drop target.
+    ///
+    /// The data is delivered to `drop_data_event`.
+    fn set_drop_target(&self, types: &[&str]) -> &Self {
+        if types.is_empty() {
+            set_str_attribute(self.handle(), "DROPTARGET\0", "NO\0");
+        } else {
+            set_str_attribute(self.handle(), "DROPTYPES\0", &format!("{}\0", types.join(",")));
+            set_str_attribute(self.handle(), "DROPTARGET\0", "YES\0");
+        }
+        self
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub enum Cursor {
     // Loading cursors from application resources is purposefully not supported, as doing that
@@ -6,6 +6,7 @@
  */
 
 use super::control_prelude::*;
+use super::image::ImageAttribute;
 
 #[derive(Clone)]
 pub struct Button(HandleRc);
@@ -48,6 +49,7 @@ impl_control_traits!(Button);
 impl ActiveAttribute for Button {}
 impl CanFocusAttribute for Button {}
 impl ExpandAttribute for Button {}
+impl ImageAttribute for Button {}
 impl MinMaxSizeAttribute for Button {}
 impl TipAttribute for Button {}
 impl TitleAttribute for Button {}
@@ -68,3 +70,10 @@ thread_local!(
 extern fn button_action_cb(ih: *mut Ihandle) -> c_int {
     simple_callback(ih, &BUTTON_ACTION_CALLBACKS)
 }
+
+// Does what clicking the button does. It is used by keyboard shortcuts.
+pub fn click_button(button: &Button) {
+    if button.active() {
+        button_action_cb(button.handle());
+    }
+}
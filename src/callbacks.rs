@@ -6,14 +6,19 @@
  */
 
 use std::any::Any;
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::collections::{hash_map, HashMap};
 use std::marker::PhantomData;
 use std::ops::{CoerceUnsized};
+use std::ffi::CStr;
 use std::panic::{self, AssertUnwindSafe};
+use std::path::PathBuf;
+use std::ptr;
 use std::rc::Rc;
+use std::slice;
 use std::thread::LocalKey;
-use libc::{c_int, c_char, c_float};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use libc::{c_int, c_char, c_float, c_void};
 use iup_sys::*;
 use smallvec::SmallVec;
 #[cfg(windows)]
@@ -660,3 +665,303 @@ impl_callbacks! {
         }
     }
 }
+
+#[derive(Clone)]
+pub struct DropFilesArgs {
+    pub path: PathBuf,
+    /// The index of the file among the files dropped at the same time. Files are delivered from
+    /// the last index down to 0, so the callback is called with an index of 0 last.
+    pub index: usize,
+    /// The number of files dropped at the same time.
+    pub count: usize,
+    /// The position of the drop relative to the control's top left corner.
+    pub x: i32,
+    pub y: i32,
+    _dummy: (),
+}
+
+// IUP only passes the index of each file, and the first file delivered has the highest index, so
+// the number of files is remembered for each control until the last file is delivered.
+thread_local!(
+    static DROP_FILES_COUNTS: RefCell<HashMap<*mut Ihandle, usize>> = RefCell::new(HashMap::new())
+);
+
+impl_callbacks! {
+    // Returning `CallbackAction::Ignore` skips the rest of the files dropped at the same time.
+    // The control has to be made a drop target with `set_drop_files_target`.
+    trait DropFilesCallback {
+        "DROPFILES_CB\0" => drop_files_event {
+            DROP_FILES_CALLBACKS<FnMut(&DropFilesArgs) -> CallbackAction, DropFilesCallbackToken>
+        }
+        unsafe extern fn drop_files_cb(ih: *mut Ihandle,
+                                       filename: *const c_char,
+                                       num: c_int,
+                                       x: c_int,
+                                       y: c_int)
+                                       -> c_int {
+            let index = num.max(0) as usize;
+            let count = DROP_FILES_COUNTS.with(|counts| {
+                *counts.borrow_mut().entry(ih).or_insert(index + 1)
+            });
+            let action = with_callbacks(ih, &DROP_FILES_CALLBACKS, |cbs| {
+                let args = DropFilesArgs {
+                    path: PathBuf::from(&*CStr::from_ptr(filename).to_string_lossy()),
+                    index: index,
+                    count: count,
+                    x: x as i32,
+                    y: y as i32,
+                    _dummy: (),
+                };
+                let mut action = CallbackAction::Default;
+                for cb in cbs {
+                    match (&mut *cb.1.borrow_mut())(&args) {
+                        CallbackAction::Default => {},
+                        cb_action => action = cb_action,
+                    }
+                }
+                action.to_int()
+            });
+            if index == 0 || action == IUP_IGNORE {
+                DROP_FILES_COUNTS.with(|counts| counts.borrow_mut().remove(&ih));
+            }
+            action
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DragEndAction {
+    Move,
+    Copy,
+    /// The drag was canceled or the data could not be dropped.
+    Canceled,
+}
+
+// The data for a drag is asked for by DRAGDATASIZE_CB and then copied into IUP's buffer by
+// DRAGDATA_CB, so it is kept here between the two calls.
+thread_local!(
+    static DRAG_DATA: RefCell<HashMap<*mut Ihandle, Vec<u8>>> = RefCell::new(HashMap::new())
+);
+
+unsafe extern fn drag_data_cb(ih: *mut Ihandle,
+                              _type: *const c_char,
+                              data: *mut c_void,
+                              size: c_int)
+                              -> c_int {
+    if let Some(bytes) = DRAG_DATA.with(|drag_data| drag_data.borrow_mut().remove(&ih)) {
+        let len = bytes.len().min(size.max(0) as usize);
+        ptr::copy_nonoverlapping(bytes.as_ptr(), data as *mut u8, len);
+    }
+    IUP_DEFAULT
+}
+
+impl_callbacks! {
+    // The control has to be made a drag source with `set_drag_source`.
+    trait DragSourceCallbacks {
+        // Returning `CallbackAction::Ignore` prevents the drag from starting.
+        "DRAGBEGIN_CB\0" => drag_begin_event {
+            DRAG_BEGIN_CALLBACKS<FnMut(i32, i32) -> CallbackAction, DragBeginCallbackToken>
+        }
+        unsafe extern fn drag_begin_cb(ih: *mut Ihandle, x: c_int, y: c_int) -> c_int {
+            with_callbacks(ih, &DRAG_BEGIN_CALLBACKS, |cbs| {
+                let mut action = CallbackAction::Default;
+                for cb in cbs {
+                    match (&mut *cb.1.borrow_mut())(x as i32, y as i32) {
+                        CallbackAction::Default => {},
+                        cb_action => action = cb_action,
+                    }
+                }
+                action.to_int()
+            })
+        }
+
+        // Called with the type the drop target asked for. The data from the last callback to
+        // return `Some` is dropped.
+        "DRAGDATASIZE_CB\0" => drag_data_event {
+            DRAG_DATA_CALLBACKS<FnMut(&str) -> Option<Vec<u8>>, DragDataCallbackToken>
+        }
+        unsafe extern fn drag_data_size_cb(ih: *mut Ihandle, type_name: *const c_char) -> c_int {
+            with_callbacks(ih, &DRAG_DATA_CALLBACKS, |cbs| {
+                let type_name = CStr::from_ptr(type_name).to_string_lossy();
+                let mut bytes = None;
+                for cb in cbs {
+                    if let Some(cb_bytes) = (&mut *cb.1.borrow_mut())(&type_name) {
+                        bytes = Some(cb_bytes);
+                    }
+                }
+                let bytes = match bytes {
+                    Some(bytes) => bytes,
+                    None => return 0,
+                };
+                let size = bytes.len() as c_int;
+                DRAG_DATA.with(|drag_data| drag_data.borrow_mut().insert(ih, bytes));
+                IupSetCallback(ih, "DRAGDATA_CB\0".as_ptr() as *const c_char,
+                               ::std::mem::transmute::<_, Icallback>(drag_data_cb as usize));
+                size
+            })
+        }
+
+        "DRAGEND_CB\0" => drag_end_event {
+            DRAG_END_CALLBACKS<FnMut(DragEndAction), DragEndCallbackToken>
+        }
+        unsafe extern fn drag_end_cb(ih: *mut Ihandle, action: c_int) -> c_int {
+            DRAG_DATA.with(|drag_data| drag_data.borrow_mut().remove(&ih));
+            with_callbacks(ih, &DRAG_END_CALLBACKS, |cbs| {
+                let action = match action {
+                    1 => DragEndAction::Move,
+                    0 => DragEndAction::Copy,
+                    _ => DragEndAction::Canceled,
+                };
+                for cb in cbs {
+                    (&mut *cb.1.borrow_mut())(action);
+                }
+                IUP_DEFAULT
+            })
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct DropDataArgs<'a> {
+    /// The type of the data, which is one of the types passed to `set_drop_target`.
+    pub type_name: &'a str,
+    pub data: &'a [u8],
+    /// The position of the drop relative to the control's top left corner.
+    pub x: i32,
+    pub y: i32,
+    _dummy: (),
+}
+
+impl_callbacks! {
+    // The control has to be made a drop target with `set_drop_target`.
+    trait DropTargetCallbacks {
+        "DROPDATA_CB\0" => drop_data_event {
+            DROP_DATA_CALLBACKS<FnMut(&DropDataArgs), DropDataCallbackToken>
+        }
+        unsafe extern fn drop_data_cb(ih: *mut Ihandle,
+                                      type_name: *const c_char,
+                                      data: *mut c_void,
+                                      size: c_int,
+                                      x: c_int,
+                                      y: c_int)
+                                      -> c_int {
+            with_callbacks(ih, &DROP_DATA_CALLBACKS, |cbs| {
+                let type_name = CStr::from_ptr(type_name).to_string_lossy();
+                let data = if data.is_null() || size <= 0 {
+                    &[][..]
+                } else {
+                    slice::from_raw_parts(data as *const u8, size as usize)
+                };
+                let args = DropDataArgs {
+                    type_name: &type_name,
+                    data: data,
+                    x: x as i32,
+                    y: y as i32,
+                    _dummy: (),
+                };
+                for cb in cbs {
+                    (&mut *cb.1.borrow_mut())(&args);
+                }
+                IUP_DEFAULT
+            })
+        }
+
+        // Called as the mouse moves over the control while data is dragged over it.
+        "DROPMOTION_CB\0" => drop_motion_event {
+            DROP_MOTION_CALLBACKS<FnMut(&MotionArgs), DropMotionCallbackToken>
+        }
+        unsafe extern fn drop_motion_cb(ih: *mut Ihandle,
+                                        x: c_int,
+                                        y: c_int,
+                                        status: *const c_char)
+                                        -> c_int {
+            with_callbacks(ih, &DROP_MOTION_CALLBACKS, |cbs| {
+                let args = MotionArgs {
+                    x: x as i32,
+                    y: y as i32,
+                    status: KeyboardMouseStatus::from_cstr(status),
+                    _dummy: (),
+                };
+                for cb in cbs {
+                    (&mut *cb.1.borrow_mut())(&args);
+                }
+                IUP_DEFAULT
+            })
+        }
+    }
+}
+
+// A value passed to `drag_value` stays here until the drop target takes it or the next value is
+// dragged. Only an ID is put in the drag data. The ID includes a key picked when the first value
+// is dragged so that data dragged from another process is not mistaken for a value from this one.
+thread_local!(
+    static DRAG_VALUE: RefCell<Option<(u64, Box<Any>)>> = RefCell::new(None)
+);
+thread_local!(static NEXT_DRAG_VALUE_ID: Cell<u64> = Cell::new(0));
+thread_local!(
+    static DRAG_VALUE_KEY: u64 = {
+        let time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or(Duration::from_secs(0));
+        let address = &time as *const Duration as u64;
+        (time.as_secs() << 30) ^ time.subsec_nanos() as u64 ^ address.rotate_left(32)
+    }
+);
+const DRAG_VALUE_MAGIC: &'static [u8; 8] = b"ccvalue\0";
+
+fn push_u64_le(bytes: &mut Vec<u8>, n: u64) {
+    for i in 0..8 {
+        bytes.push((n >> (i * 8)) as u8);
+    }
+}
+
+fn read_u64_le(bytes: &[u8]) -> u64 {
+    bytes.iter().enumerate().fold(0, |n, (i, &byte)| n | (byte as u64) << (i * 8))
+}
+
+/// Stores a value so that it can be dragged to another control in the same process without
+/// serializing it. The returned bytes only identify the value and can be returned from a
+/// `drag_data_event` callback. The drop target gets the value back with `take_drag_value`.
+///
+/// Only one value is stored at a time, so a value that is never dropped is dropped when the next
+/// value is stored.
+pub fn drag_value<T: Any>(value: T) -> Vec<u8> {
+    let id = NEXT_DRAG_VALUE_ID.with(|next_id| {
+        let id = next_id.get();
+        next_id.set(id.wrapping_add(1));
+        id
+    });
+    DRAG_VALUE.with(|drag_value| *drag_value.borrow_mut() = Some((id, Box::new(value))));
+    let mut bytes = Vec::with_capacity(24);
+    bytes.extend_from_slice(DRAG_VALUE_MAGIC);
+    push_u64_le(&mut bytes, DRAG_VALUE_KEY.with(|key| *key));
+    push_u64_le(&mut bytes, id);
+    bytes
+}
+
+/// Takes the value identified by data passed to a `drop_data_event` callback. Returns `None` if
+/// the data was not returned by `drag_value` in this process, if the value has already been taken
+/// or replaced, or if the value is not a `T`. The value is left in place if it is not a `T`.
+pub fn take_drag_value<T: Any>(data: &[u8]) -> Option<T> {
+    if data.len() != 24 || &data[..8] != DRAG_VALUE_MAGIC ||
+       read_u64_le(&data[8..16]) != DRAG_VALUE_KEY.with(|key| *key) {
+        return None;
+    }
+    let id = read_u64_le(&data[16..24]);
+    DRAG_VALUE.with(|drag_value| {
+        let mut drag_value = drag_value.borrow_mut();
+        match drag_value.take() {
+            Some((value_id, value)) if value_id == id => {
+                match value.downcast::<T>() {
+                    Ok(value) => Some(*value),
+                    Err(value) => {
+                        *drag_value = Some((value_id, value));
+                        None
+                    },
+                }
+            },
+            other => {
+                *drag_value = other;
+                None
+            },
+        }
+    })
+}
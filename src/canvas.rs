@@ -6,6 +6,41 @@
  */
 
 use super::control_prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use attributes::set_handle;
+use super::{Color, Image, LineStyle, Rect};
+use super::handle_rc::add_ldestroy_callback;
+
+// iup-sys doesn't have bindings to IUP's drawing functions. These are the signatures before
+// IUP 3.25, which matches the IUP that iup-sys binds.
+extern {
+    fn IupDrawBegin(ih: *mut Ihandle);
+    fn IupDrawEnd(ih: *mut Ihandle);
+    fn IupDrawSetClipRect(ih: *mut Ihandle, x1: c_int, y1: c_int, x2: c_int, y2: c_int);
+    fn IupDrawResetClip(ih: *mut Ihandle);
+    fn IupDrawLine(ih: *mut Ihandle, x1: c_int, y1: c_int, x2: c_int, y2: c_int);
+    fn IupDrawRectangle(ih: *mut Ihandle, x1: c_int, y1: c_int, x2: c_int, y2: c_int);
+    fn IupDrawText(ih: *mut Ihandle, text: *const c_char, len: c_int, x: c_int, y: c_int);
+    fn IupDrawImage(ih: *mut Ihandle, name: *const c_char, make_inactive: c_int, x: c_int, y: c_int);
+    fn IupDrawGetTextSize(ih: *mut Ihandle, text: *const c_char, w: *mut c_int, h: *mut c_int);
+}
+
+// The image last drawn with `draw_rgba` on a canvas. It is drawn again without being recreated
+// when a repaint draws the same pixels, which is common when a window is uncovered.
+struct DrawnImage {
+    image: Image,
+    // IupDrawImage only takes an image's name, so each canvas gives its image a unique name.
+    name: String,
+    width: u32,
+    height: u32,
+    pixels: Vec<u8>,
+}
+
+thread_local!(
+    static DRAWN_IMAGES: RefCell<HashMap<*mut Ihandle, DrawnImage>> = RefCell::new(HashMap::new())
+);
 
 #[derive(Clone)]
 pub struct Canvas(HandleRc);
@@ -19,9 +54,144 @@ impl Canvas {
         }
     }
 
+    pub unsafe fn from_handle(handle: *mut Ihandle) -> Canvas {
+        // got to already be IupOpen()ed
+        assert!(CStr::from_ptr(IupGetClassName(handle)).to_string_lossy() == "canvas");
+        Canvas(HandleRc::new(handle))
+    }
+
     pub fn draw_size(&self) -> (i32, i32) {
         get_int_int_attribute(self.handle(), "DRAWSIZE\0")
     }
+
+    /// Starts drawing on the canvas with IUP's drawing functions. What is drawn is shown when
+    /// the returned `CanvasDraw` is dropped. This must only be called while handling
+    /// `action_event`.
+    pub fn begin_draw(&self) -> CanvasDraw {
+        unsafe { IupDrawBegin(self.handle()); }
+        CanvasDraw { canvas: self }
+    }
+
+    /// Draws RGBA pixels like `CanvasDraw::rgba` and nothing else. This must only be called while
+    /// handling `action_event`.
+    pub fn draw_rgba(&self, x: i32, y: i32, width: u32, height: u32, pixels: &[u8]) {
+        self.begin_draw().rgba(x, y, width, height, pixels);
+    }
+}
+
+/// Draws on a canvas. Text is drawn with the canvas's font. It is returned by
+/// `Canvas::begin_draw`.
+pub struct CanvasDraw<'a> {
+    canvas: &'a Canvas,
+}
+
+impl<'a> CanvasDraw<'a> {
+    /// Draws 8-bit-per-channel RGBA pixels, stored left to right, top to bottom, with their top
+    /// left corner at (x, y). Drawing the same pixels as last time reuses the image IUP was given
+    /// then.
+    ///
+    /// Panics if `pixels` is not `width * height * 4` bytes long.
+    pub fn rgba(&mut self, x: i32, y: i32, width: u32, height: u32, pixels: &[u8]) {
+        let ih = self.canvas.handle();
+        DRAWN_IMAGES.with(|images| {
+            let mut images = images.borrow_mut();
+            if !images.contains_key(&ih) {
+                add_ldestroy_callback(ih, |ih| {
+                    let drawn = DRAWN_IMAGES.with(|images| images.borrow_mut().remove(&ih));
+                    if let Some(drawn) = drawn {
+                        unsafe { set_handle(&drawn.name, ptr::null_mut()); }
+                    }
+                });
+            }
+            let drawn = images.entry(ih).or_insert_with(|| DrawnImage {
+                image: Image::with_rgba(width, height, pixels),
+                name: format!("_CLEAR_COAT_CANVAS_IMAGE_{:p}\0", ih),
+                width: width,
+                height: height,
+                pixels: pixels.to_vec(),
+            });
+            if drawn.width != width || drawn.height != height || drawn.pixels != pixels {
+                drawn.image = Image::with_rgba(width, height, pixels);
+                drawn.width = width;
+                drawn.height = height;
+                drawn.pixels.clear();
+                drawn.pixels.extend_from_slice(pixels);
+            }
+            unsafe {
+                set_handle(&drawn.name, drawn.image.handle());
+                IupDrawImage(ih, drawn.name.as_ptr() as *const c_char, 0, x, y);
+            }
+        });
+    }
+
+    /// Limits drawing to the specified rectangle, or to the whole canvas if `clip` is `None`.
+    pub fn set_clip(&mut self, clip: Option<Rect>) {
+        let ih = self.canvas.handle();
+        unsafe {
+            match clip {
+                Some(r) => IupDrawSetClipRect(ih, r.x, r.y, r.x + r.width - 1, r.y + r.height - 1),
+                None => IupDrawResetClip(ih),
+            }
+        }
+    }
+
+    /// Draws a line from (x0, y0) to (x1, y1), including both end points.
+    pub fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, width: u32, style: LineStyle,
+                color: Color) {
+        let style = match style {
+            LineStyle::Solid => "STROKE\0",
+            LineStyle::Dashed => "STROKE_DASH\0",
+            LineStyle::Dotted => "STROKE_DOT\0",
+        };
+        self.set_pen(style, width, color);
+        unsafe { IupDrawLine(self.canvas.handle(), x0, y0, x1, y1); }
+    }
+
+    /// Draws the outline of a rectangle one pixel wide, inside the rectangle.
+    pub fn rect(&mut self, rect: Rect, color: Color) {
+        self.set_pen("STROKE\0", 1, color);
+        unsafe {
+            IupDrawRectangle(self.canvas.handle(), rect.x, rect.y,
+                             rect.x + rect.width - 1, rect.y + rect.height - 1);
+        }
+    }
+
+    pub fn fill_rect(&mut self, rect: Rect, color: Color) {
+        self.set_pen("FILL\0", 1, color);
+        unsafe {
+            IupDrawRectangle(self.canvas.handle(), rect.x, rect.y,
+                             rect.x + rect.width - 1, rect.y + rect.height - 1);
+        }
+    }
+
+    /// Draws text with its top left corner at (x, y).
+    pub fn text(&mut self, x: i32, y: i32, text: &str, color: Color) {
+        self.set_pen("STROKE\0", 1, color);
+        unsafe {
+            IupDrawText(self.canvas.handle(), text.as_ptr() as *const c_char, text.len() as c_int, x, y);
+        }
+    }
+
+    /// Returns the width and height of `text` when it is drawn.
+    pub fn text_size(&self, text: &str) -> (i32, i32) {
+        let text = CString::new(text.replace('\0', "")).unwrap();
+        let (mut w, mut h) = (0, 0);
+        unsafe { IupDrawGetTextSize(self.canvas.handle(), text.as_ptr(), &mut w, &mut h); }
+        (w, h)
+    }
+
+    fn set_pen(&self, style: &str, width: u32, color: Color) {
+        let ih = self.canvas.handle();
+        set_str_attribute(ih, "DRAWCOLOR\0", &color.to_str());
+        set_str_attribute(ih, "DRAWSTYLE\0", style);
+        set_str_attribute(ih, "DRAWLINEWIDTH\0", &format!("{}\0", width.max(1)));
+    }
+}
+
+impl<'a> Drop for CanvasDraw<'a> {
+    fn drop(&mut self) {
+        unsafe { IupDrawEnd(self.canvas.handle()); }
+    }
 }
 
 impl_control_traits!(Canvas);
@@ -30,6 +200,8 @@ impl ActiveAttribute for Canvas {}
 impl CanFocusAttribute for Canvas {}
 impl CanvasAttributes for Canvas {}
 impl CursorAttribute for Canvas {}
+impl DropFilesTargetAttribute for Canvas {}
+impl DragDropAttribute for Canvas {}
 impl ExpandAttribute for Canvas {}
 impl MinMaxSizeAttribute for Canvas {}
 impl ScrollbarAttribute for Canvas {}
@@ -42,3 +214,6 @@ impl EnterLeaveWindowCallbacks for Canvas {}
 impl ButtonCallback for Canvas {}
 impl CanvasCallbacks for Canvas {}
 impl ResizeCallback for Canvas {}
+impl DropFilesCallback for Canvas {}
+impl DragSourceCallbacks for Canvas {}
+impl DropTargetCallbacks for Canvas {}
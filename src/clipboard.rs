@@ -0,0 +1,166 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+use super::control_prelude::*;
+use attributes::set_attribute_ptr;
+use libc::c_void;
+use std::ffi::CStr;
+use std::slice;
+use super::Image;
+
+#[cfg(not(windows))]
+#[link(name = "gobject-2.0")]
+extern {
+    fn g_object_unref(object: *mut c_void);
+}
+
+// Frees an image returned by the NATIVEIMAGE attribute, which is an HGLOBAL holding a DIB on
+// Windows and a GdkPixbuf on GTK.
+#[cfg(windows)]
+unsafe fn free_native_image(handle: *mut c_void) {
+    ::kernel32::GlobalFree(handle as ::winapi::HGLOBAL);
+}
+
+#[cfg(not(windows))]
+unsafe fn free_native_image(handle: *mut c_void) {
+    g_object_unref(handle);
+}
+
+/// Access to the system clipboard.
+///
+/// A `Clipboard` doesn't hold any data itself, so it is fine to create one every time you need to
+/// access the clipboard.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use clear_coat::*;
+/// let clipboard = Clipboard::new();
+/// clipboard.set_text("Hello");
+/// ```
+#[derive(Clone)]
+pub struct Clipboard(HandleRc);
+
+impl Clipboard {
+    pub fn new() -> Self {
+        unsafe {
+            ::iup_open();
+            let ih = IupClipboard();
+            Clipboard(HandleRc::new(ih))
+        }
+    }
+
+    /// Returns the text on the clipboard or `None` if there isn't any text on it.
+    pub fn text(&self) -> Option<String> {
+        unsafe {
+            let val = get_attribute_ptr(self.handle(), "TEXT\0");
+            if val.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(val).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    /// Replaces the contents of the clipboard with the specified text.
+    pub fn set_text(&self, text: &str) -> &Self {
+        set_str_attribute(self.handle(), "TEXT\0", text);
+        self
+    }
+
+    pub fn is_text_available(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "TEXTAVAILABLE\0") == "YES"
+        }
+    }
+
+    /// Returns the image on the clipboard or `None` if there isn't an image on it.
+    pub fn image(&self) -> Option<Image> {
+        unsafe {
+            let native = get_attribute_ptr(self.handle(), "NATIVEIMAGE\0") as *mut c_void;
+            if native.is_null() {
+                return None;
+            }
+            let image = Image::from_native_handle(native);
+            free_native_image(native);
+            image
+        }
+    }
+
+    /// Replaces the contents of the clipboard with the specified image.
+    pub fn set_image(&self, image: &Image) -> &Self {
+        // The clipboard copies the image, so it doesn't need to keep a reference to it.
+        unsafe {
+            set_attribute_handle(self.handle(), "IMAGE\0", image.handle());
+        }
+        self
+    }
+
+    pub fn is_image_available(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "IMAGEAVAILABLE\0") == "YES"
+        }
+    }
+
+    /// Registers a custom format so that its data can be set and retrieved. Registering the
+    /// same format multiple times is fine.
+    ///
+    /// Format names are usually MIME types like `"text/html"` on GTK and names like `"HTML Format"`
+    /// on Windows.
+    pub fn register_format(&self, format: &str) -> &Self {
+        set_str_attribute(self.handle(), "ADDFORMAT\0", format);
+        self
+    }
+
+    /// Returns true if there is data of the specified custom format on the clipboard. The format
+    /// must have been registered using `register_format`.
+    pub fn is_format_available(&self, format: &str) -> bool {
+        set_str_attribute(self.handle(), "FORMAT\0", format);
+        unsafe {
+            get_str_attribute_slice(self.handle(), "FORMATAVAILABLE\0") == "YES"
+        }
+    }
+
+    /// Returns the data of the specified custom format on the clipboard or `None` if there
+    /// isn't any data of that format. The format must have been registered using
+    /// `register_format`.
+    pub fn format_data(&self, format: &str) -> Option<Vec<u8>> {
+        set_str_attribute(self.handle(), "FORMAT\0", format);
+        unsafe {
+            // FORMATDATASIZE is only set after FORMATDATA is retrieved.
+            let data = get_attribute_ptr(self.handle(), "FORMATDATA\0");
+            if data.is_null() {
+                return None;
+            }
+            let size = get_str_attribute_slice(self.handle(), "FORMATDATASIZE\0");
+            size.parse().ok().map(|size| slice::from_raw_parts(data as *const u8, size).to_vec())
+        }
+    }
+
+    /// Replaces the contents of the clipboard with data of the specified custom format. The
+    /// format must have been registered using `register_format`.
+    pub fn set_format_data(&self, format: &str, data: &[u8]) -> &Self {
+        set_str_attribute(self.handle(), "FORMAT\0", format);
+        // FORMATDATASIZE has to be set before FORMATDATA. The data is copied when FORMATDATA is
+        // set.
+        set_str_attribute(self.handle(), "FORMATDATASIZE\0", &format!("{}\0", data.len()));
+        unsafe {
+            set_attribute_ptr(self.handle(), "FORMATDATA\0", data.as_ptr() as *const c_char);
+        }
+        self
+    }
+
+    /// Removes all data from the clipboard.
+    pub fn clear(&self) -> &Self {
+        unsafe {
+            set_attribute_ptr(self.handle(), "TEXT\0", ptr::null());
+        }
+        self
+    }
+}
+
+impl_control_traits!(Clipboard);
@@ -0,0 +1,128 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+use super::control_prelude::*;
+use std::ffi::CStr;
+
+// iup-sys doesn't have bindings to IupConfig.
+extern {
+    fn IupConfig() -> *mut Ihandle;
+    fn IupConfigLoad(ih: *mut Ihandle) -> c_int;
+    fn IupConfigSave(ih: *mut Ihandle) -> c_int;
+    fn IupConfigSetVariableStr(ih: *mut Ihandle, group: *const c_char, key: *const c_char, value: *const c_char);
+    fn IupConfigGetVariableStr(ih: *mut Ihandle, group: *const c_char, key: *const c_char) -> *const c_char;
+}
+
+/// Settings saved in an INI file in the user's config directory, like `~/.config` on Linux or
+/// the AppData folder on Windows. Values are stored by key in named groups.
+///
+/// Changes are only written to the file by `save`.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use clear_coat::*;
+/// let config = Config::new("MyApp");
+/// let _ = config.load();
+/// let count = config.int("Stats", "RunCount").unwrap_or(0);
+/// config.set_int("Stats", "RunCount", count + 1);
+/// config.save().expect("could not save settings");
+/// ```
+#[derive(Clone)]
+pub struct Config(HandleRc);
+
+impl Config {
+    pub fn handle(&self) -> *mut Ihandle {
+        assert!(!self.0.get().is_null(), "attempted to use destroyed config");
+        ::check_thread();
+        self.0.get()
+    }
+
+    /// Creates an empty config for the app. The app name is used as the file name.
+    pub fn new(app_name: &str) -> Config {
+        unsafe {
+            ::iup_open();
+            let ih = IupConfig();
+            set_str_attribute(ih, "APP_NAME\0", app_name);
+            Config(HandleRc::new(ih))
+        }
+    }
+
+    /// Loads the values from the config file. Returns an error if the file doesn't exist yet or
+    /// couldn't be read.
+    pub fn load(&self) -> Result<(), ()> {
+        unsafe {
+            if IupConfigLoad(self.handle()) == 0 { Ok(()) } else { Err(()) }
+        }
+    }
+
+    /// Writes all the values to the config file, creating it if needed.
+    pub fn save(&self) -> Result<(), ()> {
+        unsafe {
+            if IupConfigSave(self.handle()) == 0 { Ok(()) } else { Err(()) }
+        }
+    }
+
+    /// Returns the path of the config file. It is only known after calling `load` or `save`.
+    pub fn file_name(&self) -> String {
+        get_str_attribute(self.handle(), "FILENAME\0")
+    }
+
+    /// Returns the value of a key, or `None` if it isn't set.
+    pub fn string(&self, group: &str, key: &str) -> Option<String> {
+        let mut group_buf = SmallVec::<[u8; 64]>::new();
+        let mut key_buf = SmallVec::<[u8; 64]>::new();
+        unsafe {
+            let value = IupConfigGetVariableStr(self.handle(),
+                                                str_to_c_vec(group, &mut group_buf),
+                                                str_to_c_vec(key, &mut key_buf));
+            if value.is_null() {
+                None
+            } else {
+                Some(CStr::from_ptr(value).to_string_lossy().into_owned())
+            }
+        }
+    }
+
+    pub fn set_string(&self, group: &str, key: &str, value: &str) {
+        let mut group_buf = SmallVec::<[u8; 64]>::new();
+        let mut key_buf = SmallVec::<[u8; 64]>::new();
+        let mut value_buf = SmallVec::<[u8; 64]>::new();
+        unsafe {
+            IupConfigSetVariableStr(self.handle(),
+                                    str_to_c_vec(group, &mut group_buf),
+                                    str_to_c_vec(key, &mut key_buf),
+                                    str_to_c_vec(value, &mut value_buf));
+        }
+    }
+
+    /// Returns the value of a key, or `None` if it isn't set or isn't an integer.
+    pub fn int(&self, group: &str, key: &str) -> Option<i32> {
+        self.string(group, key).and_then(|s| s.trim().parse().ok())
+    }
+
+    pub fn set_int(&self, group: &str, key: &str, value: i32) {
+        self.set_string(group, key, &value.to_string());
+    }
+
+    /// Returns the value of a key, or `None` if it isn't set or isn't a number.
+    pub fn float(&self, group: &str, key: &str) -> Option<f64> {
+        self.string(group, key).and_then(|s| s.trim().parse().ok())
+    }
+
+    pub fn set_float(&self, group: &str, key: &str, value: f64) {
+        self.set_string(group, key, &value.to_string());
+    }
+}
+
+// A config isn't a control, so it only implements `UnwrapHandle`.
+unsafe impl UnwrapHandle for Config {
+    fn try_unwrap_handle(self) -> Result<*mut Ihandle, Self> {
+        assert!(!self.0.get().is_null(), "attempted to use destroyed config");
+        self.0.try_unwrap().map_err(Config)
+    }
+}
@@ -30,6 +30,8 @@ pub use super::attributes::{
     CanFocusAttribute,
     CanvasAttributes,
     CursorAttribute,
+    DragDropAttribute,
+    DropFilesTargetAttribute,
     ExpandAttribute,
     MinMaxSizeAttribute,
     OrientationAttribute,
@@ -51,6 +53,9 @@ pub use super::callbacks::{
     ValueChangedCallback,
     CanvasCallbacks,
     ResizeCallback,
+    DropFilesCallback,
+    DragSourceCallbacks,
+    DropTargetCallbacks,
     Event,
     Token,
 };
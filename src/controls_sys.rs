@@ -0,0 +1,39 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+// iup-sys only has bindings to the core IUP library, so the functions from the additional
+// controls libraries are declared here.
+
+use std::sync::{Once, ONCE_INIT};
+use libc::c_int;
+use iup_sys::Ihandle;
+
+#[link(name = "iupcontrols")]
+extern {
+    pub fn IupControlsOpen() -> c_int;
+    pub fn IupMatrixList() -> *mut Ihandle;
+}
+
+#[link(name = "iupmatrixex")]
+extern {
+    pub fn IupMatrixExOpen();
+    pub fn IupMatrixEx() -> *mut Ihandle;
+}
+
+static CONTROLS_OPEN: Once = ONCE_INIT;
+
+/// Like `iup_open`, but also initializes the controls library. Must be called before creating
+/// any control from it.
+pub fn controls_open() {
+    ::iup_open();
+    CONTROLS_OPEN.call_once(|| {
+        unsafe {
+            IupControlsOpen();
+            IupMatrixExOpen();
+        }
+    });
+}
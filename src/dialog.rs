@@ -10,14 +10,24 @@ use std::ffi::CStr;
 use std::mem;
 use super::{
     Button,
+    Config,
+    Image,
+    Rect,
     ScreenPosition,
     Menu,
+    MouseButton,
     Popup,
+    Shortcuts,
 };
+use super::attributes::get_attribute_handle;
 use super::containers::Container;
+use super::global::{self, Monitor};
+use super::image::set_image_attribute;
+use super::shortcuts::dialog_shortcuts;
 use super::extra_refs::{
     ExtraRefKey,
     add_extra_ref,
+    get_extra_ref,
     remove_extra_ref,
 };
 
@@ -25,6 +35,10 @@ use super::extra_refs::{
 pub struct Dialog(HandleRc);
 
 const EXTRA_REF_MENU: ExtraRefKey = ExtraRefKey(0);
+const EXTRA_REF_PARENT_DIALOG: ExtraRefKey = ExtraRefKey(7);
+const EXTRA_REF_ICON: ExtraRefKey = ExtraRefKey(8);
+const EXTRA_REF_TRAY_IMAGE: ExtraRefKey = ExtraRefKey(9);
+const EXTRA_REF_TRAY_MENU: ExtraRefKey = ExtraRefKey(10);
 
 impl Dialog {
     pub fn new() -> Dialog {
@@ -109,10 +123,406 @@ impl Dialog {
         }
     }
 
+    pub fn resizable(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "RESIZE\0") == "YES"
+        }
+    }
+
+    /// Sets whether the user can resize the dialog. It can only be changed before the dialog is
+    /// mapped. The default is true.
+    pub fn set_resizable(&self, resizable: bool) -> &Self {
+        set_str_attribute(self.handle(), "RESIZE\0", if resizable { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    pub fn has_max_box(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "MAXBOX\0") == "YES"
+        }
+    }
+
+    /// Sets whether the title bar has a maximize button. It can only be changed before the dialog
+    /// is mapped.
+    pub fn set_max_box(&self, max_box: bool) -> &Self {
+        set_str_attribute(self.handle(), "MAXBOX\0", if max_box { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    pub fn has_min_box(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "MINBOX\0") == "YES"
+        }
+    }
+
+    /// Sets whether the title bar has a minimize button. It can only be changed before the dialog
+    /// is mapped.
+    pub fn set_min_box(&self, min_box: bool) -> &Self {
+        set_str_attribute(self.handle(), "MINBOX\0", if min_box { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    pub fn has_menu_box(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "MENUBOX\0") == "YES"
+        }
+    }
+
+    /// Sets whether the title bar has the system menu and close button. It can only be changed
+    /// before the dialog is mapped.
+    pub fn set_menu_box(&self, menu_box: bool) -> &Self {
+        set_str_attribute(self.handle(), "MENUBOX\0", if menu_box { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    pub fn has_border(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "BORDER\0") == "YES"
+        }
+    }
+
+    /// Sets whether the dialog has a border. It can only be changed before the dialog is mapped.
+    pub fn set_border(&self, border: bool) -> &Self {
+        set_str_attribute(self.handle(), "BORDER\0", if border { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    pub fn fullscreen(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "FULLSCREEN\0") == "YES"
+        }
+    }
+
+    /// Makes the dialog fill the screen without a title bar or border, or restores it.
+    pub fn set_fullscreen(&self, fullscreen: bool) -> &Self {
+        set_str_attribute(self.handle(), "FULLSCREEN\0", if fullscreen { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Returns the placement the dialog will be shown with. IUP resets the placement to
+    /// `Normal` after the dialog is shown, so use `show_event` to know when the dialog is
+    /// maximized or minimized.
+    pub fn placement(&self) -> Placement {
+        unsafe {
+            Placement::from_str(&get_str_attribute_slice(self.handle(), "PLACEMENT\0"))
+        }
+    }
+
+    /// Sets how the dialog is placed the next time it is shown. Call `show` or `show_xy`
+    /// afterward to change the placement of a dialog that is already visible.
+    pub fn set_placement(&self, placement: Placement) -> &Self {
+        set_str_attribute(self.handle(), "PLACEMENT\0", placement.to_str());
+        self
+    }
+
+    pub fn topmost(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "TOPMOST\0") == "YES"
+        }
+    }
+
+    /// Sets whether the dialog is always shown on top of other windows.
+    pub fn set_topmost(&self, topmost: bool) -> &Self {
+        set_str_attribute(self.handle(), "TOPMOST\0", if topmost { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    pub fn shrink(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "SHRINK\0") == "YES"
+        }
+    }
+
+    /// Sets whether the dialog can be made smaller than the natural size of its children.
+    pub fn set_shrink(&self, shrink: bool) -> &Self {
+        set_str_attribute(self.handle(), "SHRINK\0", if shrink { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Returns true if the dialog is being shown with `popup`, which blocks other dialogs.
+    pub fn is_modal(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "MODAL\0") == "YES"
+        }
+    }
+
+    /// Returns the dialog this dialog is owned by, if there is one.
+    pub fn parent_dialog(&self) -> Option<Dialog> {
+        let ih = get_attribute_handle(self.handle(), "PARENTDIALOG\0");
+        if ih.is_null() { None } else { Some(unsafe { Dialog::from_handle(ih) }) }
+    }
+
+    /// Sets the dialog this dialog is owned by. An owned dialog is always shown on top of its
+    /// parent and is minimized and closed with it. It can only be changed before the dialog is
+    /// mapped. The dialog keeps a reference to its parent.
+    pub fn set_parent_dialog(&self, parent: Option<&Dialog>) -> &Self {
+        remove_extra_ref(self.handle(), EXTRA_REF_PARENT_DIALOG);
+        match parent {
+            Some(parent) => unsafe {
+                set_attribute_handle(self.handle(), "PARENTDIALOG\0", parent.handle());
+                add_extra_ref(self.handle(), EXTRA_REF_PARENT_DIALOG, HandleRc::new(parent.handle()));
+            },
+            None => reset_attribute(self.handle(), "PARENTDIALOG\0"),
+        }
+        self
+    }
+
+    /// Sets the icon shown in the title bar and taskbar, or resets it to the default if `icon`
+    /// is `None`. The dialog keeps a reference to the image.
+    pub fn set_icon(&self, icon: Option<&Image>) -> &Self {
+        set_image_attribute(self.handle(), "ICON\0", EXTRA_REF_ICON, icon);
+        self
+    }
+
+    pub fn tray(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "TRAY\0") == "YES"
+        }
+    }
+
+    /// Sets whether the dialog has an icon in the system tray. The icon is shown even when the
+    /// dialog is hidden, so a hidden dialog with a tray icon can run in the background. The
+    /// image must be set with `set_tray_image` for the icon to appear.
+    pub fn set_tray(&self, tray: bool) -> &Self {
+        set_str_attribute(self.handle(), "TRAY\0", if tray { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Sets the image of the tray icon. The dialog keeps a reference to the image.
+    pub fn set_tray_image(&self, image: Option<&Image>) -> &Self {
+        set_image_attribute(self.handle(), "TRAYIMAGE\0", EXTRA_REF_TRAY_IMAGE, image);
+        self
+    }
+
+    pub fn tray_tip(&self) -> String {
+        get_str_attribute(self.handle(), "TRAYTIP\0")
+    }
+
+    /// Sets the tip shown when the mouse is over the tray icon.
+    ///
+    /// If the tip is shown in a balloon, setting the tip shows the balloon, so the balloon
+    /// options must be set before the tip.
+    pub fn set_tray_tip(&self, tip: &str) -> &Self {
+        set_str_attribute(self.handle(), "TRAYTIP\0", tip);
+        self
+    }
+
+    pub fn tray_tip_balloon(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "TRAYTIPBALLOON\0") == "YES"
+        }
+    }
+
+    /// Sets whether the tray tip is shown in a balloon. Only supported on Windows.
+    pub fn set_tray_tip_balloon(&self, balloon: bool) -> &Self {
+        set_str_attribute(self.handle(), "TRAYTIPBALLOON\0", if balloon { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Sets the title shown in the tray tip balloon. Only supported on Windows.
+    pub fn set_tray_tip_balloon_title(&self, title: &str) -> &Self {
+        set_str_attribute(self.handle(), "TRAYTIPBALLOONTITLE\0", title);
+        self
+    }
+
+    /// Sets a menu to pop up at the mouse position when the tray icon is clicked with the right
+    /// mouse button, or removes it if `menu` is `None`. The menu is shown after the
+    /// `tray_click_event` callbacks are called, unless one of them returns
+    /// `CallbackAction::Ignore`. The dialog keeps a reference to the menu.
+    pub fn set_tray_menu(&self, menu: Option<&Menu>) -> &Self {
+        remove_extra_ref(self.handle(), EXTRA_REF_TRAY_MENU);
+        if let Some(menu) = menu {
+            unsafe {
+                add_extra_ref(self.handle(), EXTRA_REF_TRAY_MENU, HandleRc::new(menu.handle()));
+                // The menu is shown by the callback, so it is needed even if no callbacks have
+                // been added.
+                IupSetCallback(self.handle(), "TRAYCLICK_CB\0".as_ptr() as *const c_char,
+                               mem::transmute::<_, Icallback>(tray_click_cb as usize));
+            }
+        }
+        self
+    }
+
+    /// Returns the keyboard shortcuts of the dialog. Every call returns the same shortcuts.
+    pub fn shortcuts(&self) -> Shortcuts {
+        dialog_shortcuts(self)
+    }
+
+    /// Returns the position of the dialog's top left corner and its size in screen coordinates,
+    /// or `None` if the dialog isn't mapped, since its size isn't known until it is. A dialog
+    /// is mapped when it is first shown.
+    pub fn screen_rect(&self) -> Option<Rect> {
+        if get_attribute_ptr(self.handle(), "WID\0").is_null() {
+            return None;
+        }
+        let (x, y) = unsafe {
+            let x = get_str_attribute_slice(self.handle(), "X\0");
+            let y = get_str_attribute_slice(self.handle(), "Y\0");
+            match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => (x, y),
+                _ => return None,
+            }
+        };
+        let (width, height) = get_int_int_attribute(self.handle(), "RASTERSIZE\0");
+        Some(Rect::new(x, y, width, height))
+    }
+
+    /// Returns the monitor that contains the largest part of the dialog, or `None` if the
+    /// dialog isn't mapped or IUP doesn't report any monitors.
+    pub fn monitor(&self) -> Option<Monitor> {
+        let rect = match self.screen_rect() {
+            Some(rect) => rect,
+            None => return None,
+        };
+        global::monitor_containing(&global::monitors(), rect).cloned()
+    }
+
+    /// Shows the dialog centered on the specified monitor. The dialog is mapped first so that
+    /// its natural size is known, and `Err` is returned if it can't be.
+    pub fn show_centered_on(&self, monitor: &Monitor) -> Result<(), ()> {
+        unsafe { IupMap(self.handle()); }
+        let rect = match self.screen_rect() {
+            Some(rect) => rect,
+            None => return Err(()),
+        };
+        let x = monitor.rect.x + (monitor.rect.width - rect.width) / 2;
+        let y = monitor.rect.y + (monitor.rect.height - rect.height) / 2;
+        self.show_xy(ScreenPosition::Absolute(x), ScreenPosition::Absolute(y))
+    }
+
+    /// Returns the position closest to (`x`, `y`) where the whole dialog would be visible on one
+    /// monitor. Use this to restore a saved position that could be off-screen because a monitor
+    /// was disconnected or the resolution changed. If the dialog isn't mapped, its size isn't
+    /// known, so only its top left corner is kept on a monitor.
+    pub fn clamp_to_monitors(&self, x: i32, y: i32) -> (i32, i32) {
+        let (width, height) = self.screen_rect().map_or((0, 0), |rect| (rect.width, rect.height));
+        let clamped = global::clamp_to_monitors(&global::monitors(), Rect::new(x, y, width, height));
+        (clamped.x, clamped.y)
+    }
+
+    /// Saves the dialog's position, size, and whether it is maximized in the group `name` of
+    /// the config. Call it before the dialog is hidden, like in `close_event`. If the dialog is
+    /// maximized, the position and size it had before being maximized are kept.
+    pub fn save_geometry(&self, config: &Config, name: &str) {
+        let maximized = unsafe {
+            get_str_attribute_slice(self.handle(), "MAXIMIZED\0") == "YES"
+        };
+        config.set_int(name, "Maximized", if maximized { 1 } else { 0 });
+        if let (false, Some(rect)) = (maximized, self.screen_rect()) {
+            config.set_int(name, "X", rect.x);
+            config.set_int(name, "Y", rect.y);
+            config.set_int(name, "Width", rect.width);
+            config.set_int(name, "Height", rect.height);
+        }
+    }
+
+    /// Shows the dialog with the geometry saved by `save_geometry`. The saved position is
+    /// moved so that the dialog is entirely on a monitor, and the size is reduced to fit the
+    /// monitor, so the dialog never appears off-screen after monitors change. If nothing was
+    /// saved, the dialog is shown centered on the primary monitor.
+    pub fn show_restored(&self, config: &Config, name: &str) -> Result<(), ()> {
+        let saved = (config.int(name, "X"), config.int(name, "Y"),
+                     config.int(name, "Width"), config.int(name, "Height"));
+        if config.int(name, "Maximized") == Some(1) {
+            self.set_placement(Placement::Maximized);
+        }
+        match saved {
+            (Some(x), Some(y), Some(width), Some(height)) if width > 0 && height > 0 => {
+                let monitors = global::monitors();
+                let mut rect = Rect::new(x, y, width, height);
+                if let Some(monitor) = global::monitor_containing(&monitors, rect) {
+                    rect.width = rect.width.min(monitor.rect.width);
+                    rect.height = rect.height.min(monitor.rect.height);
+                }
+                let rect = global::clamp_to_monitors(&monitors, rect);
+                set_str_attribute(self.handle(), "RASTERSIZE\0", &format!("{}x{}\0", rect.width, rect.height));
+                self.show_xy(ScreenPosition::Absolute(rect.x), ScreenPosition::Absolute(rect.y))
+            },
+            _ => match global::primary_monitor() {
+                Some(monitor) => self.show_centered_on(&monitor),
+                None => self.show_xy(ScreenPosition::Center, ScreenPosition::Center),
+            },
+        }
+    }
+
+    /// Converts a size in dialog units, which the SIZE attribute uses, to pixels, which the
+    /// RASTERSIZE attribute uses. A dialog unit is 1/4 of the width and 1/8 of the height of a
+    /// character in the dialog's font.
+    pub fn dialog_units_to_pixels(&self, width: i32, height: i32) -> (i32, i32) {
+        let (char_width, char_height) = get_int_int_attribute(self.handle(), "CHARSIZE\0");
+        (width * char_width / 4, height * char_height / 8)
+    }
+
+    /// Converts a size in pixels to dialog units. See `dialog_units_to_pixels`. If the font has
+    /// no character size, which happens if it couldn't be loaded, the size is 0.
+    pub fn pixels_to_dialog_units(&self, width: i32, height: i32) -> (i32, i32) {
+        let (char_width, char_height) = get_int_int_attribute(self.handle(), "CHARSIZE\0");
+        (if char_width > 0 { width * 4 / char_width } else { 0 },
+         if char_height > 0 { height * 8 / char_height } else { 0 })
+    }
+
     pub fn show_event<'a>(&'a self) -> Event<'a, FnMut(ShowState) -> CallbackAction, ShowCallbackToken>
     where &'a Self: CoerceUnsized<&'a Control> {
         Event::new(self as &Control, &SHOW_CALLBACKS)
     }
+
+    /// Called when the user tries to close the dialog, like by clicking the close button.
+    /// Returning `CallbackAction::Ignore` keeps the dialog open, and `CallbackAction::Close`
+    /// closes it and exits the main loop.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use clear_coat::*;
+    /// # fn confirm_quit() -> bool { true }
+    /// let dialog = Dialog::new();
+    /// dialog.close_event().add(|| {
+    ///     if confirm_quit() { CallbackAction::Default } else { CallbackAction::Ignore }
+    /// });
+    /// ```
+    pub fn close_event<'a>(&'a self) -> Event<'a, FnMut() -> CallbackAction, CloseCallbackToken>
+    where &'a Self: CoerceUnsized<&'a Control> {
+        Event::new(self as &Control, &CLOSE_CALLBACKS)
+    }
+
+    /// Called when the tray icon is clicked. Each click calls the callbacks once when the button
+    /// is pressed and once when it is released. Returning `CallbackAction::Close` exits the
+    /// main loop.
+    pub fn tray_click_event<'a>(&'a self)
+    -> Event<'a, FnMut(&TrayClickArgs) -> CallbackAction, TrayClickCallbackToken>
+    where &'a Self: CoerceUnsized<&'a Control> {
+        Event::new(self as &Control, &TRAY_CLICK_CALLBACKS)
+    }
+}
+
+/// How a dialog is placed when it is shown.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Placement {
+    Normal,
+    Maximized,
+    Minimized,
+    /// Fills the screen, but keeps the title bar and border unlike `Dialog::set_fullscreen`.
+    Full,
+}
+
+impl Placement {
+    fn from_str(s: &str) -> Placement {
+        match s {
+            "MAXIMIZED" => Placement::Maximized,
+            "MINIMIZED" => Placement::Minimized,
+            "FULL" => Placement::Full,
+            _ => Placement::Normal,
+        }
+    }
+
+    fn to_str(self) -> &'static str {
+        match self {
+            Placement::Normal => "NORMAL\0",
+            Placement::Maximized => "MAXIMIZED\0",
+            Placement::Minimized => "MINIMIZED\0",
+            Placement::Full => "FULL\0",
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -168,6 +578,73 @@ extern fn show_cb(ih: *mut Ihandle, state: c_int) -> c_int {
     })
 }
 
+callback_token!(CloseCallbackToken);
+thread_local!(
+    static CLOSE_CALLBACKS: CallbackRegistry<FnMut() -> CallbackAction, CloseCallbackToken> =
+        CallbackRegistry::new("CLOSE_CB\0", unsafe { mem::transmute::<_, Icallback>(close_cb as usize) })
+);
+extern fn close_cb(ih: *mut Ihandle) -> c_int {
+    with_callbacks(ih, &CLOSE_CALLBACKS, |cbs| {
+        let mut action = CallbackAction::Default;
+        for cb in cbs {
+            match (&mut *cb.1.borrow_mut())() {
+                CallbackAction::Default => {},
+                cb_action => action = cb_action,
+            }
+        }
+        action.to_int()
+    })
+}
+
+#[derive(Clone)]
+pub struct TrayClickArgs {
+    /// The mouse button that was clicked. It is only ever `Button1`, `Button2`, or `Button3`.
+    pub button: MouseButton,
+    pub pressed: bool,
+    pub double_click: bool,
+    _dummy: (),
+}
+
+callback_token!(TrayClickCallbackToken);
+thread_local!(
+    static TRAY_CLICK_CALLBACKS: CallbackRegistry<FnMut(&TrayClickArgs) -> CallbackAction, TrayClickCallbackToken> =
+        CallbackRegistry::new("TRAYCLICK_CB\0", unsafe { mem::transmute::<_, Icallback>(tray_click_cb as usize) })
+);
+extern fn tray_click_cb(ih: *mut Ihandle, button: c_int, pressed: c_int, dclick: c_int) -> c_int {
+    // Unlike in BUTTON_CB, the button is passed as a number, not a character.
+    let button = match button {
+        1 => MouseButton::Button1,
+        2 => MouseButton::Button2,
+        3 => MouseButton::Button3,
+        _ => return IUP_DEFAULT,
+    };
+    let pressed = pressed != 0;
+    let action = with_callbacks(ih, &TRAY_CLICK_CALLBACKS, |cbs| {
+        let args = TrayClickArgs {
+            button: button,
+            pressed: pressed,
+            double_click: dclick != 0,
+            _dummy: (),
+        };
+        let mut action = CallbackAction::Default;
+        for cb in cbs {
+            match (&mut *cb.1.borrow_mut())(&args) {
+                CallbackAction::Default => {},
+                cb_action => action = cb_action,
+            }
+        }
+        action.to_int()
+    });
+    if let (MouseButton::Button3, false) = (button, pressed) {
+        if action != IUP_IGNORE {
+            if let Some(menu) = get_extra_ref(ih, EXTRA_REF_TRAY_MENU).map(Menu::from_handle_rc) {
+                let _ = menu.popup(ScreenPosition::MousePos, ScreenPosition::MousePos);
+            }
+        }
+    }
+    action
+}
+
 impl_control_traits!(Dialog);
 
 impl Container for Dialog {}
@@ -175,6 +652,8 @@ impl Popup for Dialog {}
 
 impl ActiveAttribute for Dialog {}
 impl CursorAttribute for Dialog {}
+impl DropFilesTargetAttribute for Dialog {}
+impl DragDropAttribute for Dialog {}
 impl ExpandAttribute for Dialog {}
 impl MinMaxSizeAttribute for Dialog {}
 impl TipAttribute for Dialog {}
@@ -185,3 +664,6 @@ impl MenuCommonCallbacks for Dialog {}
 impl GetKillFocusCallbacks for Dialog {}
 impl EnterLeaveWindowCallbacks for Dialog {}
 impl ResizeCallback for Dialog {}
+impl DropFilesCallback for Dialog {}
+impl DragSourceCallbacks for Dialog {}
+impl DropTargetCallbacks for Dialog {}
@@ -57,3 +57,9 @@ pub fn remove_extra_ref(ih: *mut Ihandle, key: ExtraRefKey) -> bool {
         false
     })
 }
+
+pub fn get_extra_ref(ih: *mut Ihandle, key: ExtraRefKey) -> Option<HandleRc> {
+    EXTRA_REFS.with(|map| {
+        map.borrow().get(&ih).and_then(|vec| vec.iter().find(|r| r.0 == key).map(|r| r.1.clone()))
+    })
+}
@@ -0,0 +1,269 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+//! Global attributes that apply to the whole application or describe the system it is
+//! running on.
+
+use std::ptr;
+use attributes::{
+    get_attribute_ptr,
+    get_int_int_attribute,
+    get_str_attribute,
+    get_str_attribute_slice,
+    set_str_attribute,
+};
+use super::{Color, Font, Rect};
+
+// Passing a null handle gets or sets a global attribute.
+fn get_global(name: &str) -> String {
+    ::iup_open();
+    get_str_attribute(ptr::null_mut(), name)
+}
+
+fn set_global(name: &str, value: &str) {
+    ::iup_open();
+    set_str_attribute(ptr::null_mut(), name, value);
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Driver {
+    Win32,
+    Gtk,
+    Motif,
+    /// A driver this library doesn't know about, like one added in a newer version of IUP, with
+    /// the name IUP reports for it.
+    Other(String),
+}
+
+impl Driver {
+    fn from_str(s: &str) -> Self {
+        match s {
+            "Win32" => Driver::Win32,
+            "GTK" => Driver::Gtk,
+            "Motif" => Driver::Motif,
+            _ => Driver::Other(s.to_owned()),
+        }
+    }
+}
+
+/// The language used for the text of IUP's predefined dialogs.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Language {
+    English,
+    Portuguese,
+    Spanish,
+}
+
+impl Language {
+    fn from_str(s: &str) -> Self {
+        match s {
+            "ENGLISH" => Language::English,
+            "PORTUGUESE" => Language::Portuguese,
+            "SPANISH" => Language::Spanish,
+            _ => panic!("unknown Language"),
+        }
+    }
+
+    fn to_str(self) -> &'static str {
+        match self {
+            Language::English => "ENGLISH\0",
+            Language::Portuguese => "PORTUGUESE\0",
+            Language::Spanish => "SPANISH\0",
+        }
+    }
+}
+
+/// Returns the font used by controls that don't have a font set.
+pub fn default_font() -> Font {
+    Font::from_str(&get_global("DEFAULTFONT\0"))
+}
+
+/// Sets the font used by controls that don't have a font set. It only affects controls created
+/// after it is set.
+pub fn set_default_font(font: &Font) {
+    set_global("DEFAULTFONT\0", &font.to_str());
+}
+
+/// Returns the default background color of dialogs.
+pub fn dlg_bg_color() -> Color {
+    Color::from_str(&get_global("DLGBGCOLOR\0"))
+}
+
+/// Sets the default background color of dialogs. It only affects dialogs created after it is set.
+pub fn set_dlg_bg_color(color: Color) {
+    set_global("DLGBGCOLOR\0", &color.to_str());
+}
+
+/// Returns the size of the primary monitor in pixels, not including the taskbar on Windows.
+pub fn screen_size() -> (u32, u32) {
+    ::iup_open();
+    let (w, h) = get_int_int_attribute(ptr::null_mut(), "SCREENSIZE\0");
+    (w as u32, h as u32)
+}
+
+/// Returns the number of bits per pixel of the screen, or `None` if the driver doesn't report
+/// it.
+pub fn screen_depth() -> Option<u32> {
+    get_global("SCREENDEPTH\0").parse().ok()
+}
+
+/// Returns the number of pixels per inch of the screen, or `None` if the driver doesn't report
+/// it.
+pub fn screen_dpi() -> Option<f32> {
+    get_global("SCREENDPI\0").parse().ok()
+}
+
+/// Returns the position and size of every monitor. The primary monitor is not necessarily first.
+pub fn monitors_info() -> Vec<Rect> {
+    get_global("MONITORSINFO\0").lines().filter(|l| !l.trim().is_empty()).map(Rect::from_str).collect()
+}
+
+/// Returns the rectangle that contains all monitors.
+pub fn virtual_screen() -> Rect {
+    Rect::from_str(&get_global("VIRTUALSCREEN\0"))
+}
+
+/// Returns the position of the mouse cursor in screen coordinates.
+pub fn cursor_pos() -> (i32, i32) {
+    ::iup_open();
+    get_int_int_attribute(ptr::null_mut(), "CURSORPOS\0")
+}
+
+/// Returns the name of the operating system, like "Win7" or "Linux".
+pub fn system() -> String {
+    get_global("SYSTEM\0")
+}
+
+/// Returns the version of the operating system.
+pub fn system_version() -> String {
+    get_global("SYSTEMVERSION\0")
+}
+
+/// Returns the language of the operating system, like "en-US" or "pt_BR".
+pub fn system_language() -> String {
+    get_global("SYSTEMLANGUAGE\0")
+}
+
+/// Returns the version of GTK or `None` if the GTK driver isn't being used.
+pub fn gtk_version() -> Option<String> {
+    ::iup_open();
+    if get_attribute_ptr(ptr::null_mut(), "GTKVERSION\0").is_null() {
+        None
+    } else {
+        Some(get_str_attribute(ptr::null_mut(), "GTKVERSION\0"))
+    }
+}
+
+/// Returns the native toolkit that IUP is using.
+pub fn driver() -> Driver {
+    ::iup_open();
+    unsafe {
+        Driver::from_str(&get_str_attribute_slice(ptr::null_mut(), "DRIVER\0"))
+    }
+}
+
+pub fn computer_name() -> String {
+    get_global("COMPUTERNAME\0")
+}
+
+pub fn user_name() -> String {
+    get_global("USERNAME\0")
+}
+
+/// Returns the language used for the text of IUP's predefined dialogs.
+pub fn language() -> Language {
+    Language::from_str(&get_global("LANGUAGE\0"))
+}
+
+/// Sets the language used for the text of IUP's predefined dialogs.
+///
+/// The default is English.
+pub fn set_language(language: Language) {
+    set_global("LANGUAGE\0", language.to_str());
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Monitor {
+    /// The position and size of the monitor in screen coordinates.
+    pub rect: Rect,
+    /// Whether this is the primary monitor. IUP doesn't report which monitor is primary, so the
+    /// monitor at the origin of the screen coordinates is assumed to be, which is where Windows
+    /// puts it. If no monitor is at the origin, like on some X11 setups, the first one is used.
+    pub primary: bool,
+    /// The number of pixels per inch. IUP only reports one DPI for the whole screen, so this is
+    /// the same for all monitors.
+    pub dpi: f32,
+}
+
+/// Returns every monitor. The primary monitor is not necessarily first.
+pub fn monitors() -> Vec<Monitor> {
+    // 96 is the DPI that systems assume when they don't know the real one.
+    let dpi = screen_dpi().unwrap_or(96.0);
+    let rects = monitors_info();
+    // See `Monitor::primary`.
+    let primary_index = rects.iter().position(|r| r.x == 0 && r.y == 0).unwrap_or(0);
+    rects.into_iter().enumerate().map(|(i, rect)| Monitor {
+        rect: rect,
+        primary: i == primary_index,
+        dpi: dpi,
+    }).collect()
+}
+
+/// Returns the primary monitor, guessed the way `Monitor::primary` describes, or `None` if IUP
+/// doesn't report any monitors.
+pub fn primary_monitor() -> Option<Monitor> {
+    let monitors = monitors();
+    monitors.iter().find(|m| m.primary).or(monitors.first()).cloned()
+}
+
+fn overlap_area(a: &Rect, b: &Rect) -> i64 {
+    let width = (a.x + a.width).min(b.x + b.width) - a.x.max(b.x);
+    let height = (a.y + a.height).min(b.y + b.height) - a.y.max(b.y);
+    if width <= 0 || height <= 0 { 0 } else { width as i64 * height as i64 }
+}
+
+// The squared distance between the closest points of two rectangles.
+fn distance_sq(a: &Rect, b: &Rect) -> i64 {
+    let dx = (b.x - (a.x + a.width)).max(a.x - (b.x + b.width)).max(0) as i64;
+    let dy = (b.y - (a.y + a.height)).max(a.y - (b.y + b.height)).max(0) as i64;
+    dx * dx + dy * dy
+}
+
+/// Returns the monitor that contains the largest part of `rect`. If `rect` isn't on any monitor,
+/// the closest monitor is returned. Returns `None` only if `monitors` is empty.
+pub fn monitor_containing(monitors: &[Monitor], rect: Rect) -> Option<&Monitor> {
+    let best_overlap = monitors.iter().max_by_key(|m| overlap_area(&m.rect, &rect));
+    match best_overlap {
+        Some(m) if overlap_area(&m.rect, &rect) > 0 => Some(m),
+        _ => monitors.iter().min_by_key(|m| distance_sq(&m.rect, &rect)),
+    }
+}
+
+/// Moves `rect` so that it is entirely on the monitor that contains most of it (or is closest to
+/// it). If `rect` is larger than the monitor, its top left corner is kept on the monitor so that
+/// the title bar of a window is visible. The size of `rect` is never changed.
+///
+/// This is useful for restoring a saved window position after a monitor has been disconnected.
+pub fn clamp_to_monitors(monitors: &[Monitor], rect: Rect) -> Rect {
+    let monitor = match monitor_containing(monitors, rect) {
+        Some(m) => m.rect,
+        None => return rect,
+    };
+    let clamp = |pos: i32, size: i32, min: i32, len: i32| {
+        if size >= len {
+            min
+        } else {
+            pos.max(min).min(min + len - size)
+        }
+    };
+    Rect {
+        x: clamp(rect.x, rect.width, monitor.x, monitor.width),
+        y: clamp(rect.y, rect.height, monitor.y, monitor.height),
+        width: rect.width,
+        height: rect.height,
+    }
+}
@@ -0,0 +1,163 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+use super::control_prelude::*;
+use super::extra_refs::{add_extra_ref, remove_extra_ref, ExtraRefKey};
+use libc::c_void;
+#[cfg(feature = "load-image")]
+use std::error;
+#[cfg(feature = "load-image")]
+use std::fmt;
+#[cfg(feature = "load-image")]
+use std::fs::File;
+#[cfg(feature = "load-image")]
+use std::io::{self, Read};
+#[cfg(feature = "load-image")]
+use std::path::Path;
+#[cfg(feature = "load-image")]
+use super::image_decode;
+
+extern {
+    fn IupGetNativeHandleImage(handle: *mut c_void) -> *mut Ihandle;
+}
+
+/// An image that can be shown on buttons, labels, menu items, and other controls.
+///
+/// The pixel data is copied into IUP when the image is created, so the source data doesn't have
+/// to be kept alive.
+#[derive(Clone)]
+pub struct Image(HandleRc);
+
+impl Image {
+    /// Creates an image from 8-bit-per-channel RGBA pixels, stored left to right, top to bottom.
+    ///
+    /// Panics if `pixels` is not `width * height * 4` bytes long.
+    pub fn with_rgba(width: u32, height: u32, pixels: &[u8]) -> Image {
+        assert!(pixels.len() == width as usize * height as usize * 4,
+                "pixel data is the wrong length for the image size");
+        unsafe {
+            ::iup_open();
+            let ih = IupImageRGBA(width as c_int, height as c_int, pixels.as_ptr());
+            Image(HandleRc::new(ih))
+        }
+    }
+
+    /// Loads a PNG or BMP image from a file. The format is detected from the file's contents,
+    /// not its extension.
+    #[cfg(feature = "load-image")]
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Image, ImageLoadError> {
+        let mut data = vec![];
+        try!(File::open(path).and_then(|mut f| f.read_to_end(&mut data)));
+        Image::load_from_memory(&data)
+    }
+
+    /// Loads a PNG or BMP image from memory. This works well with `include_bytes!` for embedding
+    /// icons in the executable.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// # use clear_coat::*;
+    /// let icon = Image::load_from_memory(include_bytes!("save.png")).unwrap();
+    /// ```
+    #[cfg(feature = "load-image")]
+    pub fn load_from_memory(data: &[u8]) -> Result<Image, ImageLoadError> {
+        let (width, height, pixels) = try!(image_decode::decode_rgba(data));
+        Ok(Image::with_rgba(width, height, &pixels))
+    }
+
+    // Creates an image by copying the pixels of a native image, like the clipboard's NATIVEIMAGE.
+    // The native image isn't released.
+    pub(crate) unsafe fn from_native_handle(handle: *mut c_void) -> Option<Image> {
+        ::iup_open();
+        let ih = IupGetNativeHandleImage(handle);
+        if ih.is_null() {
+            None
+        } else {
+            Some(Image(HandleRc::new(ih)))
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        unsafe {
+            let s = get_str_attribute_slice(self.handle(), "WIDTH\0");
+            s.parse().expect("could not convert WIDTH to an integer")
+        }
+    }
+
+    pub fn height(&self) -> u32 {
+        unsafe {
+            let s = get_str_attribute_slice(self.handle(), "HEIGHT\0");
+            s.parse().expect("could not convert HEIGHT to an integer")
+        }
+    }
+}
+
+impl_control_traits!(Image);
+
+#[cfg(feature = "load-image")]
+#[derive(Debug)]
+pub enum ImageLoadError {
+    Io(io::Error),
+    /// The data isn't a PNG or BMP image.
+    UnknownFormat,
+    /// The data is a PNG or BMP image, but it is corrupt or uses a feature that isn't supported.
+    Decode(String),
+}
+
+#[cfg(feature = "load-image")]
+impl From<io::Error> for ImageLoadError {
+    fn from(err: io::Error) -> ImageLoadError {
+        ImageLoadError::Io(err)
+    }
+}
+
+#[cfg(feature = "load-image")]
+impl fmt::Display for ImageLoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ImageLoadError::Io(ref err) => write!(f, "failed to read image: {}", err),
+            ImageLoadError::UnknownFormat => write!(f, "image is not a PNG or BMP file"),
+            ImageLoadError::Decode(ref msg) => write!(f, "failed to decode image: {}", msg),
+        }
+    }
+}
+
+#[cfg(feature = "load-image")]
+impl error::Error for ImageLoadError {
+    fn description(&self) -> &str {
+        match *self {
+            ImageLoadError::Io(ref err) => err.description(),
+            ImageLoadError::UnknownFormat => "image is not a PNG or BMP file",
+            ImageLoadError::Decode(ref msg) => msg,
+        }
+    }
+}
+
+// Sets an attribute like IMAGE or ICON to an image and keeps a reference to the image so that
+// it isn't destroyed while `ih` is using it. Each attribute needs its own `key`.
+pub fn set_image_attribute(ih: *mut Ihandle, name: &str, key: ExtraRefKey, image: Option<&Image>) {
+    remove_extra_ref(ih, key);
+    match image {
+        Some(image) => {
+            unsafe { set_attribute_handle(ih, name, image.handle()); }
+            add_extra_ref(ih, key, image.0.clone());
+        },
+        None => reset_attribute(ih, name),
+    }
+}
+
+const EXTRA_REF_IMAGE: ExtraRefKey = ExtraRefKey(2);
+
+pub trait ImageAttribute : Control {
+    /// Sets the image shown on the control, or clears it if `image` is `None`. The control
+    /// keeps a reference to the image.
+    fn set_image(&self, image: Option<&Image>) -> &Self {
+        set_image_attribute(self.handle(), "IMAGE\0", EXTRA_REF_IMAGE, image);
+        self
+    }
+}
@@ -0,0 +1,208 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+// Decoding is done in Rust instead of with IUP's IM library (IupLoadImage) so that there is one
+// less C library to build and ship with an app.
+
+use png;
+use super::image::ImageLoadError;
+
+const PNG_SIGNATURE: &'static [u8] = b"\x89PNG\r\n\x1a\n";
+const BMP_SIGNATURE: &'static [u8] = b"BM";
+
+/// Decodes a PNG or BMP image into 8-bit RGBA pixels, stored left to right, top to bottom.
+/// Returns the width, height, and pixels.
+pub fn decode_rgba(data: &[u8]) -> Result<(u32, u32, Vec<u8>), ImageLoadError> {
+    if data.starts_with(PNG_SIGNATURE) {
+        decode_png(data)
+    } else if data.starts_with(BMP_SIGNATURE) {
+        decode_bmp(data)
+    } else {
+        Err(ImageLoadError::UnknownFormat)
+    }
+}
+
+fn decode_png(data: &[u8]) -> Result<(u32, u32, Vec<u8>), ImageLoadError> {
+    let to_err = |err: png::DecodingError| ImageLoadError::Decode(err.to_string());
+
+    // The default transformations expand palettes and strip 16-bit channels to 8 bits, so the
+    // only color types that need handled here are the 8-bit ones.
+    let (info, mut reader) = try!(png::Decoder::new(data).read_info().map_err(&to_err));
+    let mut buf = vec![0; info.buffer_size()];
+    try!(reader.next_frame(&mut buf).map_err(&to_err));
+
+    let pixel_count = info.width as usize * info.height as usize;
+    let mut pixels = Vec::with_capacity(pixel_count * 4);
+    match info.color_type {
+        png::ColorType::RGBA => return Ok((info.width, info.height, buf)),
+        png::ColorType::RGB => {
+            for p in buf.chunks(3) {
+                pixels.extend_from_slice(&[p[0], p[1], p[2], 255]);
+            }
+        },
+        png::ColorType::GrayscaleAlpha => {
+            for p in buf.chunks(2) {
+                pixels.extend_from_slice(&[p[0], p[0], p[0], p[1]]);
+            }
+        },
+        png::ColorType::Grayscale => {
+            for &p in buf.iter() {
+                pixels.extend_from_slice(&[p, p, p, 255]);
+            }
+        },
+        png::ColorType::Indexed => {
+            return Err(ImageLoadError::Decode("PNG palette was not expanded".to_owned()));
+        },
+    }
+    Ok((info.width, info.height, pixels))
+}
+
+fn read_u16(data: &[u8], offset: usize) -> Result<u16, ImageLoadError> {
+    if offset + 2 > data.len() {
+        return Err(ImageLoadError::Decode("BMP file is truncated".to_owned()));
+    }
+    Ok(data[offset] as u16 | (data[offset + 1] as u16) << 8)
+}
+
+fn read_u32(data: &[u8], offset: usize) -> Result<u32, ImageLoadError> {
+    let low = try!(read_u16(data, offset)) as u32;
+    let high = try!(read_u16(data, offset + 2)) as u32;
+    Ok(low | high << 16)
+}
+
+// Returns the shift and width of the set bits in a BI_BITFIELDS mask.
+fn mask_shift_width(mask: u32) -> (u32, u32) {
+    if mask == 0 {
+        return (0, 0);
+    }
+    let shift = mask.trailing_zeros();
+    (shift, (mask >> shift).count_ones())
+}
+
+fn extract_channel(pixel: u32, mask: u32) -> u8 {
+    let (shift, width) = mask_shift_width(mask);
+    if width == 0 {
+        return 0;
+    }
+    let value = (pixel & mask) >> shift;
+    let max = (1u64 << width) - 1;
+    (value as u64 * 255 / max) as u8
+}
+
+// Supports uncompressed 1, 4, 8, 24, and 32-bit images and 16 and 32-bit BI_BITFIELDS images,
+// which covers what image editors write by default. RLE compression is not supported.
+fn decode_bmp(data: &[u8]) -> Result<(u32, u32, Vec<u8>), ImageLoadError> {
+    const BI_RGB: u32 = 0;
+    const BI_BITFIELDS: u32 = 3;
+    const FILE_HEADER_SIZE: usize = 14;
+
+    let pixel_offset = try!(read_u32(data, 10)) as usize;
+    let header_size = try!(read_u32(data, FILE_HEADER_SIZE)) as usize;
+    if header_size < 40 {
+        return Err(ImageLoadError::Decode("OS/2 BMP files are not supported".to_owned()));
+    }
+    let width = try!(read_u32(data, FILE_HEADER_SIZE + 4)) as i32;
+    let height = try!(read_u32(data, FILE_HEADER_SIZE + 8)) as i32;
+    let bit_count = try!(read_u16(data, FILE_HEADER_SIZE + 14));
+    let compression = try!(read_u32(data, FILE_HEADER_SIZE + 16));
+    let colors_used = try!(read_u32(data, FILE_HEADER_SIZE + 32)) as usize;
+
+    if width <= 0 || height == 0 {
+        return Err(ImageLoadError::Decode("BMP file has an invalid size".to_owned()));
+    }
+    // A negative height means the rows are stored top to bottom instead of bottom to top.
+    let top_down = height < 0;
+    let (width, height) = (width as usize, height.abs() as usize);
+
+    let masks = match (compression, bit_count) {
+        (BI_RGB, 16) => Some((0x7C00, 0x03E0, 0x001F, 0)),
+        (BI_RGB, 32) => Some((0x00FF0000, 0x0000FF00, 0x000000FF, 0)),
+        (BI_RGB, _) => None,
+        (BI_BITFIELDS, 16) | (BI_BITFIELDS, 32) => {
+            // The masks follow a 40 byte header or are part of a larger header.
+            let masks_offset = FILE_HEADER_SIZE + 40;
+            let alpha_mask = if header_size >= 56 {
+                try!(read_u32(data, masks_offset + 12))
+            } else {
+                0
+            };
+            Some((try!(read_u32(data, masks_offset)),
+                  try!(read_u32(data, masks_offset + 4)),
+                  try!(read_u32(data, masks_offset + 8)),
+                  alpha_mask))
+        },
+        _ => return Err(ImageLoadError::Decode("compressed BMP files are not supported".to_owned())),
+    };
+
+    let mut palette = vec![];
+    if bit_count <= 8 {
+        let palette_len = if colors_used == 0 { 1 << bit_count } else { colors_used };
+        let palette_offset = FILE_HEADER_SIZE + header_size;
+        for i in 0..palette_len {
+            let entry = palette_offset + i * 4;
+            if entry + 4 > data.len() {
+                return Err(ImageLoadError::Decode("BMP file is truncated".to_owned()));
+            }
+            // Palette entries are stored as BGRX.
+            palette.push([data[entry + 2], data[entry + 1], data[entry], 255]);
+        }
+    }
+
+    // Rows are padded to a multiple of four bytes.
+    let row_size = (width * bit_count as usize + 31) / 32 * 4;
+    if pixel_offset + row_size * height > data.len() {
+        return Err(ImageLoadError::Decode("BMP file is truncated".to_owned()));
+    }
+
+    // Some 32-bit images leave the alpha channel zeroed instead of using it. Treat those as
+    // opaque instead of fully transparent.
+    let mut any_alpha = false;
+    let mut pixels = vec![0; width * height * 4];
+    for y in 0..height {
+        let src_y = if top_down { y } else { height - 1 - y };
+        let row = &data[pixel_offset + src_y * row_size..pixel_offset + (src_y + 1) * row_size];
+        for x in 0..width {
+            let rgba = match (bit_count, masks) {
+                (1, _) | (4, _) | (8, _) => {
+                    let bit_offset = x * bit_count as usize;
+                    let byte = row[bit_offset / 8];
+                    let shift = 8 - bit_count as usize - bit_offset % 8;
+                    let index = (byte >> shift) as usize & ((1 << bit_count) - 1);
+                    match palette.get(index) {
+                        Some(color) => *color,
+                        None => return Err(ImageLoadError::Decode("BMP palette index out of range".to_owned())),
+                    }
+                },
+                (24, _) => [row[x * 3 + 2], row[x * 3 + 1], row[x * 3], 255],
+                (16, Some((r, g, b, a))) => {
+                    let pixel = row[x * 2] as u32 | (row[x * 2 + 1] as u32) << 8;
+                    let alpha = if a == 0 { 255 } else { extract_channel(pixel, a) };
+                    [extract_channel(pixel, r), extract_channel(pixel, g), extract_channel(pixel, b), alpha]
+                },
+                (32, Some((r, g, b, a))) => {
+                    let i = x * 4;
+                    let pixel = row[i] as u32 | (row[i + 1] as u32) << 8 |
+                                (row[i + 2] as u32) << 16 | (row[i + 3] as u32) << 24;
+                    // BI_RGB 32-bit images store alpha in the otherwise unused high byte.
+                    let alpha_mask = if a == 0 && compression == BI_RGB { 0xFF000000 } else { a };
+                    let alpha = if alpha_mask == 0 { 255 } else { extract_channel(pixel, alpha_mask) };
+                    [extract_channel(pixel, r), extract_channel(pixel, g), extract_channel(pixel, b), alpha]
+                },
+                _ => return Err(ImageLoadError::Decode(format!("{}-bit BMP files are not supported", bit_count))),
+            };
+            any_alpha = any_alpha || rgba[3] != 0;
+            let dest = (y * width + x) * 4;
+            pixels[dest..dest + 4].copy_from_slice(&rgba);
+        }
+    }
+    if !any_alpha {
+        for p in pixels.chunks_mut(4) {
+            p[3] = 255;
+        }
+    }
+    Ok((width as u32, height as u32, pixels))
+}
@@ -6,6 +6,7 @@
  */
 
 use super::control_prelude::*;
+use super::image::ImageAttribute;
 
 #[derive(Clone)]
 pub struct Label(HandleRc);
@@ -49,7 +50,10 @@ impl Label {
 impl_control_traits!(Label);
 
 impl ActiveAttribute for Label {}
+impl DropFilesTargetAttribute for Label {}
+impl DragDropAttribute for Label {}
 impl ExpandAttribute for Label {}
+impl ImageAttribute for Label {}
 impl MinMaxSizeAttribute for Label {}
 impl TipAttribute for Label {}
 impl TitleAttribute for Label {}
@@ -58,3 +62,6 @@ impl VisibleAttribute for Label {}
 impl MenuCommonCallbacks for Label {}
 
 impl EnterLeaveWindowCallbacks for Label {}
+impl DropFilesCallback for Label {}
+impl DragSourceCallbacks for Label {}
+impl DropTargetCallbacks for Label {}
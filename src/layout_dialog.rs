@@ -0,0 +1,99 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+// The inspector dialogs can destroy, move, and create elements of the inspected dialog. They
+// destroy elements with IupDestroy, which calls the LDESTROY_CB that `HandleRc` sets, so any
+// wrapper of a destroyed element is zeroed out the same as if the element were destroyed any
+// other way. Moving elements doesn't need any bookkeeping because a `HandleRc` only checks
+// whether its element has a parent when it is dropped. Elements the inspectors create have no
+// wrappers and are destroyed with their parents.
+
+use super::control_prelude::*;
+use super::{Dialog, Popup};
+use super::extra_refs::{ExtraRefKey, add_extra_ref};
+
+// The inspector keeps a raw pointer to the inspected element, so the element must not be
+// destroyed before the inspector.
+const EXTRA_REF_INSPECTED: ExtraRefKey = ExtraRefKey(5);
+
+/// A dialog for viewing and editing the layout of another dialog while the program is running.
+/// It shows the tree of elements and lets you change their attributes, which is useful for
+/// tuning a layout without recompiling.
+///
+/// The layout dialog keeps a reference to the inspected dialog. Elements deleted from the layout
+/// dialog are destroyed, so using a wrapper of one afterward panics.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use clear_coat::*;
+/// let dialog = Dialog::new();
+/// // ...
+/// let layout = LayoutDialog::new(&dialog);
+/// layout.show().expect("could not show layout dialog");
+/// ```
+#[derive(Clone)]
+pub struct LayoutDialog(HandleRc);
+
+impl LayoutDialog {
+    pub fn new(dialog: &Dialog) -> Self {
+        unsafe {
+            ::iup_open();
+            let ih = IupLayoutDialog(dialog.handle());
+            add_extra_ref(ih, EXTRA_REF_INSPECTED, HandleRc::new(dialog.handle()));
+            LayoutDialog(HandleRc::new(ih))
+        }
+    }
+
+    /// Shows the layout dialog without blocking. Closing it hides it.
+    pub fn show(&self) -> Result<(), ()> {
+        unsafe {
+            if IupShow(self.handle()) == IUP_NOERROR {
+                Ok(())
+            } else {
+                Err(())
+            }
+        }
+    }
+}
+
+impl_control_traits!(LayoutDialog);
+
+impl Popup for LayoutDialog {}
+
+/// A dialog for viewing and editing all the attributes and callbacks of a control while the
+/// program is running.
+///
+/// The dialog keeps a reference to the inspected control.
+#[derive(Clone)]
+pub struct ElementPropertiesDialog(HandleRc);
+
+impl ElementPropertiesDialog {
+    pub fn new(control: &Control) -> Self {
+        unsafe {
+            ::iup_open();
+            let ih = IupElementPropertiesDialog(control.handle());
+            add_extra_ref(ih, EXTRA_REF_INSPECTED, HandleRc::new(control.handle()));
+            ElementPropertiesDialog(HandleRc::new(ih))
+        }
+    }
+
+    /// Shows the properties dialog without blocking. Closing it hides it.
+    pub fn show(&self) -> Result<(), ()> {
+        unsafe {
+            if IupShow(self.handle()) == IUP_NOERROR {
+                Ok(())
+            } else {
+                Err(())
+            }
+        }
+    }
+}
+
+impl_control_traits!(ElementPropertiesDialog);
+
+impl Popup for ElementPropertiesDialog {}
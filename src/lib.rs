@@ -17,6 +17,8 @@ extern crate iup_sys;
 extern crate kernel32;
 extern crate winapi;
 extern crate smallvec;
+#[cfg(any(feature = "load-image", feature = "save-image"))]
+extern crate png;
 
 #[cfg(windows)]
 fn get_thread_id() -> isize {
@@ -54,12 +56,28 @@ mod alarm_builder;
 mod attributes;
 mod button;
 mod canvas;
+mod clipboard;
+mod config;
+#[cfg(feature = "iup-controls")]
+mod controls_sys;
 mod frame;
+mod image;
+#[cfg(feature = "load-image")]
+mod image_decode;
 mod label;
+mod layout_dialog;
 mod line_graph;
 mod list;
+#[cfg(feature = "iup-controls")]
+mod matrix;
+#[cfg(feature = "iup-controls")]
+mod matrix_list;
 mod menu;
 mod radio;
+mod raster;
+mod recent_files;
+mod shortcuts;
+mod split;
 mod tabs;
 mod text;
 mod timer;
@@ -69,22 +87,70 @@ mod file_dlg;
 mod handle_rc;
 mod extra_refs;
 
-pub use dialog::{Dialog, ShowCallbackToken, ShowState};
+pub mod global;
+
+pub use dialog::{
+    Dialog,
+    ShowCallbackToken,
+    CloseCallbackToken,
+    TrayClickCallbackToken,
+    ShowState,
+    Placement,
+    TrayClickArgs,
+};
 pub use file_dlg::{FileDlg, FileDialogType, FileExtFilter};
 pub use alarm_builder::{AlarmResult, AlarmBuilder};
 pub use button::Button;
-pub use canvas::{Canvas};
+pub use canvas::{Canvas, CanvasDraw};
+pub use clipboard::Clipboard;
+pub use config::Config;
 pub use frame::Frame;
+pub use image::Image;
+#[cfg(feature = "load-image")]
+pub use image::ImageLoadError;
 pub use label::Label;
+pub use layout_dialog::{LayoutDialog, ElementPropertiesDialog};
+pub use line_graph::{
+    LineGraph,
+    LineGraphHoverArgs,
+    LineGraphHoverToken,
+    DataPoint,
+    YAxisSide,
+    LineStyle,
+    CsvLayout,
+};
 pub use list::{List, ListActionArgs};
+#[cfg(feature = "iup-controls")]
+pub use matrix::{Matrix, MatrixValueArgs, MatrixValueEditArgs, MatrixEditionArgs};
+#[cfg(feature = "iup-controls")]
+pub use matrix_list::{
+    MatrixList,
+    MatrixListActionArgs,
+    MatrixListClickArgs,
+    MatrixListEditionArgs,
+    MatrixListCheckArgs,
+};
 pub use radio::Radio;
-pub use tabs::{Tabs, TabInfo};
+pub use recent_files::{RecentFiles, RecentFileCallbackToken};
+pub use shortcuts::{Shortcuts, Key, KeyChord};
+pub use split::Split;
+pub use tabs::{Tabs, TabInfo, TabPosition, TabChangeArgs};
 pub use text::{Text, CaretArgs};
 pub use timer::Timer;
 pub use toggle::{Toggle, ToggleState};
-pub use menu::{Menu, MenuSubitem, Item, Separator, Submenu};
+pub use menu::{Menu, MenuSubitem, Item, Separator, Submenu, MenuOpenCallbackToken, MenuCloseCallbackToken};
 pub use containers::{Container, NonDialogContainer, Fill, Hbox, Vbox, NumDiv, GridBox};
-pub use callbacks::{CallbackAction, Event, ButtonArgs, CanvasActionArgs};
+pub use callbacks::{
+    CallbackAction,
+    Event,
+    ButtonArgs,
+    CanvasActionArgs,
+    DropFilesArgs,
+    DropDataArgs,
+    DragEndAction,
+    drag_value,
+    take_drag_value,
+};
 
 // With this layout, you can glob import this module's contents but selectively import the
 // above types if you want.
@@ -94,6 +160,8 @@ pub mod common_attrs_cbs {
         ActiveAttribute,
         CanFocusAttribute,
         Cursor,
+        DragDropAttribute,
+        DropFilesTargetAttribute,
         CursorAttribute,
         Expand,
         ExpandAttribute,
@@ -107,6 +175,8 @@ pub mod common_attrs_cbs {
         VisibleAttribute,
         VisibleColumnsLinesAttribute,
     };
+    pub use image::ImageAttribute;
+    pub use menu::ContextMenu;
     pub use callbacks::{
         MenuCommonCallbacks,
         GetKillFocusCallbacks,
@@ -117,6 +187,9 @@ pub mod common_attrs_cbs {
         WheelArgs,
         CanvasCallbacks,
         ResizeCallback,
+        DropFilesCallback,
+        DragSourceCallbacks,
+        DropTargetCallbacks,
     };
 }
 
@@ -481,3 +554,154 @@ impl Orientation {
         }
     }
 }
+
+
+/// A color with 8 bits per channel.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Color {
+    pub fn new(r: u8, g: u8, b: u8) -> Color {
+        Color { r: r, g: g, b: b }
+    }
+
+    // IUP colors are stored as "r g b". Some attributes also accept "#rrggbb", but IUP always
+    // returns the first format.
+    fn from_str(s: &str) -> Self {
+        let mut parts = s.split(' ').map(|s| s.parse().expect("could not convert color to integers"));
+        let msg = "failed to split color into three parts";
+        Color {
+            r: parts.next().expect(msg),
+            g: parts.next().expect(msg),
+            b: parts.next().expect(msg),
+        }
+    }
+
+    fn to_str(self) -> String {
+        format!("{} {} {}\0", self.r, self.g, self.b)
+    }
+}
+
+
+/// A rectangle in pixels.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rect {
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        Rect { x: x, y: y, width: width, height: height }
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.x + self.width && y >= self.y && y < self.y + self.height
+    }
+
+    // Parses IUP's "x y width height" format used by MONITORSINFO and VIRTUALSCREEN.
+    fn from_str(s: &str) -> Self {
+        let mut parts = s.split_whitespace().map(|s| s.parse().expect("could not convert rectangle to integers"));
+        let msg = "failed to split rectangle into four parts";
+        Rect {
+            x: parts.next().expect(msg),
+            y: parts.next().expect(msg),
+            width: parts.next().expect(msg),
+            height: parts.next().expect(msg),
+        }
+    }
+}
+
+
+/// A font description in IUP's format, like "Segoe UI, Bold 10".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Font {
+    pub face: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strikeout: bool,
+    /// The size in points. A negative size is in pixels instead.
+    pub size: i32,
+}
+
+impl Font {
+    pub fn new(face: &str, size: i32) -> Font {
+        Font {
+            face: face.to_owned(),
+            bold: false,
+            italic: false,
+            underline: false,
+            strikeout: false,
+            size: size,
+        }
+    }
+
+    // Parses both IUP's format, "Face, Styles Size", and the Pango format used by GTK, "Face Styles
+    // Size". Unknown styles are ignored so that any font IUP reports can be read.
+    fn from_str(s: &str) -> Self {
+        let mut font = Font::new("", 0);
+        match s.find(',') {
+            Some(comma) => {
+                font.face = s[..comma].trim().to_owned();
+                for word in s[comma + 1..].split_whitespace() {
+                    if !font.set_style(word) {
+                        if let Some(size) = Font::parse_size(word) {
+                            font.size = size;
+                        }
+                    }
+                }
+            },
+            None => {
+                let mut words: Vec<&str> = s.split_whitespace().collect();
+                if let Some(size) = words.last().and_then(|word| Font::parse_size(word)) {
+                    font.size = size;
+                    words.pop();
+                }
+                while words.len() > 1 && font.set_style(words[words.len() - 1]) {
+                    words.pop();
+                }
+                font.face = words.join(" ");
+            },
+        }
+        font
+    }
+
+    // Returns false if the word isn't a style.
+    fn set_style(&mut self, word: &str) -> bool {
+        match &*word.to_lowercase() {
+            "bold" => self.bold = true,
+            "italic" => self.italic = true,
+            "underline" => self.underline = true,
+            "strikeout" => self.strikeout = true,
+            _ => return false,
+        }
+        true
+    }
+
+    // A size in pixels is negative, either written that way or with a "px" suffix in Pango.
+    fn parse_size(word: &str) -> Option<i32> {
+        let (number, sign) = if word.ends_with("px") {
+            (&word[..word.len() - 2], -1.0)
+        } else {
+            (word, 1.0)
+        };
+        number.parse::<f64>().ok().map(|size| (size * sign).round() as i32)
+    }
+
+    fn to_str(&self) -> String {
+        let mut s = format!("{},", self.face);
+        if self.bold { s.push_str(" Bold"); }
+        if self.italic { s.push_str(" Italic"); }
+        if self.underline { s.push_str(" Underline"); }
+        if self.strikeout { s.push_str(" Strikeout"); }
+        s.push_str(&format!(" {}\0", self.size));
+        s
+    }
+}
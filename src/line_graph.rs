@@ -7,23 +7,62 @@
 
 use super::control_prelude::*;
 use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::f64;
+use std::io::{self, Write};
+use std::mem;
 use std::rc::Rc;
 use std::ops::Range;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
 use super::{
+    ButtonArgs,
     Canvas,
     CanvasActionArgs,
+    CanvasDraw,
+    Color,
+    MouseButton,
+    Rect,
+    Timer,
 };
+use super::callbacks::{MotionArgs, WheelArgs};
+use super::handle_rc::add_ldestroy_callback;
+use super::raster::{self, Raster, GLYPH_HEIGHT};
 
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct DataPoint {
-    x: f64,
-    y: f64,
+    pub x: f64,
+    pub y: f64,
 }
 
+impl DataPoint {
+    pub fn new(x: f64, y: f64) -> DataPoint {
+        DataPoint { x: x, y: y }
+    }
+}
+
+// A bucket without any points has a `min_y` of infinity and a `max_y` of negative infinity.
+#[derive(Copy, Clone)]
 pub struct MergedDataPoint {
     min_y: f64,
     max_y: f64,
 }
 
+impl MergedDataPoint {
+    fn empty() -> MergedDataPoint {
+        MergedDataPoint { min_y: f64::INFINITY, max_y: f64::NEG_INFINITY }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.min_y > self.max_y
+    }
+
+    fn add(&mut self, other: &MergedDataPoint) {
+        self.min_y = self.min_y.min(other.min_y);
+        self.max_y = self.max_y.max(other.max_y);
+    }
+}
+
 /// Don't create pyramids smaller than this many points because they are less likely to be used.
 /// Should be a little larger than the smallest a graph will typically be.
 const MIN_PYRAMID_LEVEL_SIZE: usize = 1000;
@@ -44,25 +83,320 @@ pub struct PyramidLevel {
     merged_points: Vec<MergedDataPoint>,
 }
 
+/// How `LineGraph::write_csv` arranges the points of the series.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CsvLayout {
+    /// An `x` column followed by one column per series. Each row has the points of all series
+    /// at one x value, and series without a point at that x value have an empty cell.
+    Aligned,
+    /// `series`, `x`, and `y` columns with one row per point.
+    Long,
+}
+
+/// Which side of the plot a y axis is drawn on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum YAxisSide {
+    Left,
+    Right,
+}
+
+/// The pattern a series' line is drawn with.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineStyle {
+    Solid,
+    Dashed,
+    Dotted,
+}
+
+impl LineStyle {
+    // Returns whether the pixel at `counter` along the line is drawn.
+    fn is_on(self, counter: u32) -> bool {
+        match self {
+            LineStyle::Solid => true,
+            LineStyle::Dashed => counter % 9 < 6,
+            LineStyle::Dotted => counter % 3 == 0,
+        }
+    }
+}
+
+struct YAxis {
+    range: Range<f64>,
+    side: YAxisSide,
+    title: String,
+}
+
+impl YAxis {
+    fn new(side: YAxisSide) -> YAxis {
+        YAxis { range: 0.0..1.0, side: side, title: String::new() }
+    }
+}
+
 pub struct DataSeries {
-    // Sorted by each point's x value.
+    // Sorted by each point's x value. Only the points from `start` on are in the series; the ones
+    // before it were dropped because of `capacity` and are removed in batches so that appending
+    // to a full series isn't slow.
     points: Vec<DataPoint>,
+    start: usize,
+    capacity: Option<usize>,
+    // An index into `LineGraphData::y_axes`
     axis: u8,
+    // If empty, the legend shows a default name.
+    name: String,
+    color: Color,
+    width: u32,
+    line_style: LineStyle,
+    visible: bool,
 
+    // Sorted from the most points (smallest `x_step`) to the fewest. After old points are
+    // dropped, the levels can still have buckets before the first point, which aren't drawn.
     pyramid: Vec<PyramidLevel>,
+    // Changed every time `points` is replaced so that a pyramid built on another thread can be
+    // matched to the points it was built from.
+    generation: u64,
+    // True while the pyramid is being built on another thread. Points appended in the meantime
+    // are saved so that they can be added to the pyramid when it is done.
+    pyramid_pending: bool,
+    appended_since_build: Vec<DataPoint>,
 }
 
-pub struct LineGraphData {
-    canvas: Canvas,
+impl DataSeries {
+    fn new(points: Vec<DataPoint>, color: Color) -> DataSeries {
+        DataSeries {
+            points: points,
+            start: 0,
+            capacity: None,
+            axis: 0,
+            name: String::new(),
+            color: color,
+            width: 1,
+            line_style: LineStyle::Solid,
+            visible: true,
+            pyramid: vec![],
+            generation: 0,
+            pyramid_pending: false,
+            appended_since_build: vec![],
+        }
+    }
+
+    fn points(&self) -> &[DataPoint] {
+        &self.points[self.start..]
+    }
 
+    // Adds sorted points to the pyramid. Returns false if the pyramid has to be rebuilt because
+    // a point is before the first bucket or so far past the last one that the buckets in between
+    // would take much more memory than the points.
+    fn extend_pyramid(&mut self, points: &[DataPoint]) -> bool {
+        let (min_x, x_step, len) = match self.pyramid.first() {
+            Some(level) => (level.min_x, level.x_step, level.merged_points.len()),
+            None => return true,
+        };
+        if points.first().map_or(false, |pt| pt.x < min_x) {
+            return false;
+        }
+        // The first level has the most buckets, so it is the only one that needs checking.
+        let max_len = (len + points.len()) * 2;
+        if points.iter().any(|pt| (pt.x - min_x) / x_step >= max_len as f64) {
+            return false;
+        }
+        // Every level's `min_x` is at or before the first level's.
+        for level in self.pyramid.iter_mut() {
+            for pt in points.iter().filter(|pt| !pt.y.is_nan()) {
+                let i = ((pt.x - level.min_x) / level.x_step) as usize;
+                if i >= level.merged_points.len() {
+                    level.merged_points.resize(i + 1, MergedDataPoint::empty());
+                }
+                level.merged_points[i].add(&MergedDataPoint { min_y: pt.y, max_y: pt.y });
+            }
+        }
+        // Add levels on top as the series grows, the same as `build_pyramid` would.
+        while self.pyramid.last().map_or(false, |level| level.merged_points.len() + 1 >= MIN_PYRAMID_LEVEL_SIZE * 2) {
+            let next = merge_pyramid_level(self.pyramid.last().unwrap());
+            self.pyramid.push(next);
+        }
+        true
+    }
+
+    // Drops the oldest points over the capacity.
+    fn drop_old_points(&mut self) {
+        let len = self.points.len() - self.start;
+        match self.capacity {
+            Some(capacity) if len > capacity => self.start += len - capacity,
+            _ => return,
+        }
+        if self.start >= self.points.len() / 2 {
+            self.points.drain(..self.start);
+            self.start = 0;
+            self.trim_pyramid();
+        }
+    }
+
+    // Removes the buckets of the pyramid that are entirely before the first point. The first
+    // bucket kept can still include dropped points, so it is rebuilt from the points left.
+    fn trim_pyramid(&mut self) {
+        let points = &self.points[self.start..];
+        let first_x = match points.first() {
+            Some(pt) => pt.x,
+            None => return,
+        };
+        for level in self.pyramid.iter_mut() {
+            let count = ((first_x - level.min_x) / level.x_step).floor().max(0.0) as usize;
+            let count = count.min(level.merged_points.len());
+            level.merged_points.drain(..count);
+            level.min_x += count as f64 * level.x_step;
+            let (min_x, x_step) = (level.min_x, level.x_step);
+            if let Some(first) = level.merged_points.first_mut() {
+                let mut bucket = MergedDataPoint::empty();
+                for pt in points.iter()
+                                .take_while(|pt| (pt.x - min_x) / x_step < 1.0)
+                                .filter(|pt| !pt.y.is_nan()) {
+                    bucket.add(&MergedDataPoint { min_y: pt.y, max_y: pt.y });
+                }
+                *first = bucket;
+            }
+        }
+    }
+}
+
+struct LineGraphData {
     // the order of DataSeries determines the z-order on screen
     series: Vec<DataSeries>,
     x_axis: Range<f64>,
-    y_axes: Vec<Range<f64>>,
+    // The first axis is on the left and can't be removed.
+    y_axes: Vec<YAxis>,
+    legend_visible: bool,
+
+    // If set, the x range is scrolled to show this width up to the largest x value whenever
+    // points are appended.
+    x_scroll_window: Option<f64>,
+
+    next_generation: u64,
+    build_pyramids_in_background: bool,
+    // Pyramids being built on other threads, with the generation of the points they are for.
+    pending_pyramids: Vec<(u64, Receiver<Vec<PyramidLevel>>)>,
+    // Checks for finished pyramids while there are any pending.
+    pyramid_timer: Option<Timer>,
+
+    drag: Option<Drag>,
+    // The position of the mouse in pixels if it is over the plot area.
+    hover: Option<(i32, i32)>,
+
+    // The layout the graph was last drawn with, which mouse events use.
+    layout: Layout,
+    // The background, grid, and series as they were last drawn, with the plot area they were
+    // drawn in. Drawing only the crosshair or zoom rectangle again reuses them.
+    plot_layer: Option<(Rect, Raster)>,
+}
+
+// Where the parts of the graph are, in pixels.
+struct Layout {
+    // The area inside the axes where series are drawn.
+    plot: Rect,
+    // The area of each series' entry in the legend, or nothing if the legend is hidden.
+    legend: Vec<Rect>,
+}
+
+// What the axes, labels, legend, and overlay are drawn with. On screen, it is IUP's drawing
+// functions so that text uses the canvas's font, and when exporting, it is the software renderer.
+trait Painter {
+    fn text_size(&self, text: &str) -> (i32, i32);
+    fn text(&mut self, x: i32, y: i32, text: &str, color: Color);
+    fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, width: u32, style: LineStyle, color: Color);
+    fn rect(&mut self, rect: Rect, color: Color);
+    fn fill_rect(&mut self, rect: Rect, color: Color);
+    fn set_clip(&mut self, clip: Option<Rect>);
+}
+
+impl Painter for Raster {
+    fn text_size(&self, text: &str) -> (i32, i32) {
+        (raster::text_width(text), GLYPH_HEIGHT)
+    }
+
+    fn text(&mut self, x: i32, y: i32, text: &str, color: Color) {
+        self.draw_text(x, y, text, color);
+    }
+
+    fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, width: u32, style: LineStyle, color: Color) {
+        let mut counter = 0;
+        self.draw_line_pattern(x0 as f64, y0 as f64, x1 as f64, y1 as f64, width, color, &mut |_| {
+            counter += 1;
+            style.is_on(counter - 1)
+        });
+    }
+
+    fn rect(&mut self, rect: Rect, color: Color) {
+        self.draw_rect(rect.x, rect.y, rect.width, rect.height, color);
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        Raster::fill_rect(self, rect.x, rect.y, rect.width, rect.height, color);
+    }
+
+    fn set_clip(&mut self, clip: Option<Rect>) {
+        Raster::set_clip(self, clip);
+    }
 }
 
+impl<'a> Painter for CanvasDraw<'a> {
+    fn text_size(&self, text: &str) -> (i32, i32) {
+        CanvasDraw::text_size(self, text)
+    }
+
+    fn text(&mut self, x: i32, y: i32, text: &str, color: Color) {
+        CanvasDraw::text(self, x, y, text, color);
+    }
+
+    fn line(&mut self, x0: i32, y0: i32, x1: i32, y1: i32, width: u32, style: LineStyle, color: Color) {
+        CanvasDraw::line(self, x0, y0, x1, y1, width, style, color);
+    }
+
+    fn rect(&mut self, rect: Rect, color: Color) {
+        CanvasDraw::rect(self, rect, color);
+    }
+
+    fn fill_rect(&mut self, rect: Rect, color: Color) {
+        CanvasDraw::fill_rect(self, rect, color);
+    }
+
+    fn set_clip(&mut self, clip: Option<Rect>) {
+        CanvasDraw::set_clip(self, clip);
+    }
+}
+
+enum Drag {
+    // The last mouse position.
+    Pan(i32, i32),
+    // The mouse position where the drag started and the current position.
+    Zoom((i32, i32), (i32, i32)),
+}
+
+impl Drop for LineGraphData {
+    fn drop(&mut self) {
+        // A running timer keeps a reference to itself, so it has to be stopped to be destroyed.
+        if let Some(timer) = self.pyramid_timer.take() {
+            timer.set_running(false);
+        }
+    }
+}
+
+/// A graph of one or more series of points connected by lines, drawn on a `Canvas`.
+///
+/// The graph stays fast with millions of points per series because at most a few lines are
+/// drawn per pixel column.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use clear_coat::*;
+/// let graph = LineGraph::new();
+/// graph.add_series((0..100).map(|i| DataPoint::new(i as f64, (i as f64 / 10.0).sin())).collect());
+/// graph.autoscale_all();
+/// let dialog = Dialog::with_child(&graph);
+/// ```
 #[derive(Clone)]
-pub struct LineGraph(Rc<RefCell<LineGraphData>>);
+pub struct LineGraph {
+    canvas: Canvas,
+    data: Rc<RefCell<LineGraphData>>,
+}
 
 /*
 To draw the screen, the mipmap >= to the number of pixels on screen is chosen and reduced to
@@ -72,45 +406,1537 @@ The midmaps for a DataSeries could be calculated on a different thread. When it
 is swapped in, and painting becomes faster.
 */
 
+// How often to check whether pyramids being built on other threads are done, in milliseconds.
+const PYRAMID_POLL_TIME: u32 = 50;
+
+// Each notch of the mouse wheel zooms by this factor.
+const WHEEL_ZOOM_FACTOR: f64 = 0.8;
+// Zooming stops when the visible span is this small compared to the values in it. Any smaller and
+// the ticks and pixel positions would be lost to floating-point rounding.
+const MIN_RELATIVE_SPAN: f64 = 1e-12;
+// A rubber band smaller than this in either direction doesn't zoom so that a click doesn't zoom
+// in a huge amount.
+const MIN_ZOOM_RECT_SIZE: i32 = 4;
+const CROSSHAIR_COLOR: Color = Color { r: 160, g: 160, b: 160 };
+const ZOOM_RECT_COLOR: Color = Color { r: 80, g: 80, b: 80 };
+
+// IUP key codes from iupkey.h, which iup-sys doesn't have
+const K_ESC: u32 = 0xFF1B;
+const K_HOME: u32 = 0xFF50;
+
+const BACKGROUND_COLOR: Color = Color { r: 255, g: 255, b: 255 };
+const AXIS_COLOR: Color = Color { r: 0, g: 0, b: 0 };
+const GRID_COLOR: Color = Color { r: 225, g: 225, b: 225 };
+const TEXT_COLOR: Color = Color { r: 0, g: 0, b: 0 };
+
+// Series are given these colors in order unless a color is set.
+const SERIES_COLORS: [Color; 8] = [
+    Color { r: 31, g: 119, b: 180 },
+    Color { r: 255, g: 127, b: 14 },
+    Color { r: 44, g: 160, b: 44 },
+    Color { r: 214, g: 39, b: 40 },
+    Color { r: 148, g: 103, b: 189 },
+    Color { r: 140, g: 86, b: 75 },
+    Color { r: 227, g: 119, b: 194 },
+    Color { r: 127, g: 127, b: 127 },
+];
+
+const HIDDEN_SERIES_COLOR: Color = Color { r: 190, g: 190, b: 190 };
+const LEGEND_SAMPLE_LENGTH: i32 = 20;
+const TICK_LENGTH: i32 = 4;
+const LABEL_PADDING: i32 = 4;
+const OUTER_MARGIN: i32 = 10;
+
 impl LineGraph {
     pub fn new() -> Self {
         let canvas = Canvas::new();
         let data = Rc::new(RefCell::new(LineGraphData {
-            canvas: canvas.clone(),
             series: vec![],
             x_axis: 0.0..1.0,
-            y_axes: vec![0.0..1.0],
+            y_axes: vec![YAxis::new(YAxisSide::Left)],
+            legend_visible: false,
+            x_scroll_window: None,
+            next_generation: 0,
+            build_pyramids_in_background: true,
+            pending_pyramids: vec![],
+            pyramid_timer: None,
+            drag: None,
+            hover: None,
+            layout: Layout { plot: Rect::new(0, 0, 1, 1), legend: vec![] },
+            plot_layer: None,
         }));
 
+        // Capturing the canvas in its own callbacks would keep it from ever being destroyed, so
+        // only the handle is captured. The callbacks are removed when the canvas is destroyed.
+        let ih = canvas.handle();
+        let data2 = data.clone();
+        canvas.action_event().add(move |args: &CanvasActionArgs| {
+            unsafe { LineGraph::from_parts(ih, data2.clone()) }.when_painting(args)
+        });
+        let data2 = data.clone();
+        canvas.button_event().add(move |args: &ButtonArgs| {
+            unsafe { LineGraph::from_parts(ih, data2.clone()) }.when_button(args)
+        });
+        let data2 = data.clone();
+        canvas.motion_event().add(move |args: &MotionArgs| {
+            unsafe { LineGraph::from_parts(ih, data2.clone()) }.when_motion(args)
+        });
+        let data2 = data.clone();
+        canvas.wheel_event().add(move |args: &WheelArgs| {
+            unsafe { LineGraph::from_parts(ih, data2.clone()) }.when_wheel(args)
+        });
+        let data2 = data.clone();
+        canvas.key_press_event().add(move |key, pressed| {
+            unsafe { LineGraph::from_parts(ih, data2.clone()) }.when_key_press(key, pressed)
+        });
         let data2 = data.clone();
-        canvas.action_event().add(move |args: &CanvasActionArgs|
-            LineGraph(data2.clone()).when_painting(args)
-        );
+        canvas.leave_window_event().add(move || {
+            unsafe { LineGraph::from_parts(ih, data2.clone()) }.set_hover(None)
+        });
 
-        LineGraph(data)
+        // The pyramid timer uses the canvas's handle, so it must not run after the canvas is
+        // destroyed.
+        let weak_data = Rc::downgrade(&data);
+        add_ldestroy_callback(ih, move |_| {
+            if let Some(data) = weak_data.upgrade() {
+                let timer = data.borrow_mut().pyramid_timer.take();
+                if let Some(timer) = timer {
+                    timer.set_running(false);
+                }
+            }
+        });
+
+        LineGraph { canvas: canvas, data: data }
+    }
+
+    unsafe fn from_parts(canvas_ih: *mut Ihandle, data: Rc<RefCell<LineGraphData>>) -> LineGraph {
+        LineGraph { canvas: Canvas::from_handle(canvas_ih), data: data }
+    }
+
+    /// Returns the canvas the graph is drawn on.
+    pub fn canvas(&self) -> &Canvas {
+        &self.canvas
+    }
+
+    fn when_painting(&self, _args: &CanvasActionArgs) {
+        let (width, height) = self.canvas.draw_size();
+        if width <= 0 || height <= 0 {
+            return;
+        }
+        let mut data = self.data.borrow_mut();
+        let data = &mut *data;
+        let mut draw = self.canvas.begin_draw();
+        let layout = data.layout(width, height, &draw);
+        let stale = match data.plot_layer {
+            Some((plot, ref raster)) =>
+                plot != layout.plot || raster.width() != width as u32 || raster.height() != height as u32,
+            None => true,
+        };
+        if stale {
+            let mut raster = Raster::new(width as u32, height as u32, BACKGROUND_COLOR);
+            data.draw_plot(&mut raster, layout.plot);
+            data.plot_layer = Some((layout.plot, raster));
+        }
+        if let Some((_, ref raster)) = data.plot_layer {
+            draw.rgba(0, 0, raster.width(), raster.height(), raster.pixels());
+        }
+        data.draw_axes(&mut draw, &layout);
+        data.draw_legend(&mut draw, &layout);
+        data.draw_overlay(&mut draw, layout.plot);
+        data.layout = layout;
+    }
+
+    // Draws the whole graph again the next time events are processed. When only the crosshair
+    // or zoom rectangle changed, `canvas.update()` is enough.
+    fn redraw(&self) {
+        self.data.borrow_mut().plot_layer = None;
+        self.canvas.update();
     }
 
-    fn when_painting(&self, args: &CanvasActionArgs) {
+    // The left button pans, and the right button or shift and the left button zooms to a
+    // rectangle.
+    fn when_button(&self, args: &ButtonArgs) -> CallbackAction {
+        let mut data = self.data.borrow_mut();
+        let plot = data.layout.plot;
+        if args.pressed {
+            if !plot.contains(args.x, args.y) {
+                return CallbackAction::Default;
+            }
+            unsafe { IupSetFocus(self.canvas.handle()); }
+            if let MouseButton::Button1 = args.button {
+                let clicked = data.layout.legend.iter().position(|r| r.contains(args.x, args.y));
+                if let Some(index) = clicked {
+                    let series = &mut data.series[index];
+                    series.visible = !series.visible;
+                    drop(data);
+                    self.redraw();
+                    return CallbackAction::Default;
+                }
+            }
+            data.drag = match args.button {
+                MouseButton::Button1 if !args.status.shift_pressed => Some(Drag::Pan(args.x, args.y)),
+                MouseButton::Button1 | MouseButton::Button3 =>
+                    Some(Drag::Zoom((args.x, args.y), (args.x, args.y))),
+                _ => None,
+            };
+        } else if let Some(Drag::Zoom(start, _)) = data.drag.take() {
+            let (x0, x1) = (start.0.min(args.x), start.0.max(args.x));
+            let (y0, y1) = (start.1.min(args.y), start.1.max(args.y));
+            if x1 - x0 >= MIN_ZOOM_RECT_SIZE && y1 - y0 >= MIN_ZOOM_RECT_SIZE {
+                data.x_axis = limit_zoom(data.pixel_to_x(plot, x0 as f64)..data.pixel_to_x(plot, x1 as f64));
+                for axis in 0..data.y_axes.len() {
+                    data.y_axes[axis].range = limit_zoom(data.pixel_to_y(axis, plot, y1 as f64)..data.pixel_to_y(axis, plot, y0 as f64));
+                }
+            }
+            drop(data);
+            self.redraw();
+        }
+        CallbackAction::Default
+    }
+
+    fn when_motion(&self, args: &MotionArgs) {
+        let plot = self.data.borrow().layout.plot;
+        let panned = {
+            let mut data = self.data.borrow_mut();
+            match data.drag {
+                Some(Drag::Pan(last_x, last_y)) => {
+                    data.x_axis = pan_range(&data.x_axis, last_x - args.x, plot.width);
+                    for axis in data.y_axes.iter_mut() {
+                        axis.range = pan_range(&axis.range, args.y - last_y, plot.height);
+                    }
+                    data.drag = Some(Drag::Pan(args.x, args.y));
+                    true
+                },
+                Some(Drag::Zoom(start, _)) => {
+                    data.drag = Some(Drag::Zoom(start, (args.x, args.y)));
+                    false
+                },
+                None => false,
+            }
+        };
+        // Moving the zoom rectangle only changes the overlay, which `set_hover` redraws.
+        if panned {
+            self.redraw();
+        }
+        self.set_hover(if plot.contains(args.x, args.y) { Some((args.x, args.y)) } else { None });
+    }
+
+    // Zooms the x axis around the mouse, or the y axes if control is pressed.
+    fn when_wheel(&self, args: &WheelArgs) {
+        let plot = self.data.borrow().layout.plot;
+        if !plot.contains(args.x, args.y) {
+            return;
+        }
+        {
+            let mut data = self.data.borrow_mut();
+            let factor = WHEEL_ZOOM_FACTOR.powf(args.delta as f64);
+            if args.status.control_pressed {
+                for axis in 0..data.y_axes.len() {
+                    let center = data.pixel_to_y(axis, plot, args.y as f64);
+                    data.y_axes[axis].range = zoom_range(&data.y_axes[axis].range, center, factor);
+                }
+            } else {
+                let center = data.pixel_to_x(plot, args.x as f64);
+                data.x_axis = zoom_range(&data.x_axis, center, factor);
+            }
+        }
+        self.redraw();
+    }
+
+    // Home or R resets the zoom to show all points, and Escape cancels a rubber band zoom.
+    fn when_key_press(&self, key: u32, pressed: bool) -> CallbackAction {
+        if !pressed {
+            return CallbackAction::Default;
+        }
+        match key {
+            K_HOME => self.autoscale_all(),
+            k if k == 'r' as u32 || k == 'R' as u32 => self.autoscale_all(),
+            K_ESC => {
+                self.data.borrow_mut().drag = None;
+                self.canvas.update();
+            },
+            _ => return CallbackAction::Default,
+        }
+        CallbackAction::Ignore
+    }
+
+    fn set_hover(&self, hover: Option<(i32, i32)>) {
+        let plot = self.data.borrow().layout.plot;
+        let args = {
+            let mut data = self.data.borrow_mut();
+            if data.hover == hover && hover.is_none() {
+                return;
+            }
+            data.hover = hover;
+            LineGraphHoverArgs {
+                position: hover.map(|(x, y)| {
+                    DataPoint::new(data.pixel_to_x(plot, x as f64), data.pixel_to_y(0, plot, y as f64))
+                }),
+                nearest: match hover {
+                    Some((x, _)) => {
+                        let x = data.pixel_to_x(plot, x as f64);
+                        data.series.iter()
+                                   .map(|s| if s.visible { nearest_point(s.points(), x) } else { None })
+                                   .collect()
+                    },
+                    None => vec![],
+                },
+                _dummy: (),
+            }
+        };
+        self.canvas.update();
+        with_callbacks(self.canvas.handle(), &HOVER_CALLBACKS, |cbs| {
+            for cb in cbs {
+                (&mut *cb.1.borrow_mut())(&args);
+            }
+            IUP_DEFAULT
+        });
+    }
+
+    /// Called when the mouse moves over the graph, with the point of each series that is
+    /// nearest the mouse horizontally. When the mouse leaves the graph, it is called with a
+    /// `position` of `None`.
+    pub fn hover_event<'a>(&'a self) -> Event<'a, FnMut(&LineGraphHoverArgs), LineGraphHoverToken> {
+        Event::new(self as &Control, &HOVER_CALLBACKS)
+    }
+
+    /// Adds a series to the graph and returns its index. The points don't need to be sorted.
+    /// Points with an infinite or NaN x value are ignored.
+    ///
+    /// Series are drawn in the order they were added, so the last series is on top.
+    pub fn add_series(&self, mut points: Vec<DataPoint>) -> usize {
+        remove_non_finite_x(&mut points);
+        sort_points(&mut points);
+        let index = {
+            let mut data = self.data.borrow_mut();
+            let color = SERIES_COLORS[data.series.len() % SERIES_COLORS.len()];
+            data.series.push(DataSeries::new(points, color));
+            data.series.len() - 1
+        };
+        self.start_pyramid(index);
+        self.redraw();
+        index
+    }
+
+    /// Removes a series. The indexes of the series after it are decreased by one.
+    pub fn remove_series(&self, index: usize) {
+        self.data.borrow_mut().series.remove(index);
+        self.redraw();
+    }
+
+    pub fn series_count(&self) -> usize {
+        self.data.borrow().series.len()
+    }
+
+    /// Replaces all the points of a series. The points don't need to be sorted. Points with an
+    /// infinite or NaN x value are ignored.
+    pub fn set_series_points(&self, index: usize, mut points: Vec<DataPoint>) {
+        remove_non_finite_x(&mut points);
+        sort_points(&mut points);
+        {
+            let mut data = self.data.borrow_mut();
+            let series = &mut data.series[index];
+            series.points = points;
+            series.start = 0;
+            series.pyramid.clear();
+            series.drop_old_points();
+        }
+        self.start_pyramid(index);
+        self.redraw();
+    }
+
+    /// Adds points to a series. The points don't need to be sorted, but appending points with
+    /// x values larger than the series already has is fastest. Pyramids are updated
+    /// incrementally instead of being rebuilt. Points with an infinite or NaN x value are
+    /// ignored.
+    ///
+    /// The graph is redrawn the next time events are processed, so appending many times in a
+    /// row only redraws once.
+    pub fn append_points(&self, index: usize, points: &[DataPoint]) {
+        let mut points = points.to_vec();
+        remove_non_finite_x(&mut points);
+        if points.is_empty() {
+            return;
+        }
+        sort_points(&mut points);
+        let rebuild = {
+            let mut data = self.data.borrow_mut();
+            let data = &mut *data;
+            let rebuild = {
+                let series = &mut data.series[index];
+                let in_order = series.points().last().map_or(true, |last| points[0].x >= last.x);
+                series.points.extend_from_slice(&points);
+                if !in_order {
+                    let start = series.start;
+                    sort_points(&mut series.points[start..]);
+                }
+                let rebuild = if series.pyramid_pending {
+                    series.appended_since_build.extend_from_slice(&points);
+                    false
+                } else if series.pyramid.is_empty() {
+                    series.points().len() >= MIN_PYRAMID_LEVEL_SIZE * 2
+                } else {
+                    !series.extend_pyramid(&points)
+                };
+                series.drop_old_points();
+                rebuild
+            };
+            data.scroll_x();
+            rebuild
+        };
+        if rebuild {
+            self.data.borrow_mut().series[index].pyramid.clear();
+            self.start_pyramid(index);
+        }
+        self.redraw();
+    }
 
+    /// Adds a point to a series. See `append_points`.
+    pub fn append_point(&self, index: usize, point: DataPoint) {
+        self.append_points(index, &[point]);
     }
 
+    /// Sets the maximum number of points a series keeps. When more points are added, the ones
+    /// with the smallest x values are dropped, which makes the series a ring buffer for
+    /// streaming data. `None`, the default, keeps all points.
+    pub fn set_series_capacity(&self, index: usize, capacity: Option<usize>) {
+        {
+            let mut data = self.data.borrow_mut();
+            let series = &mut data.series[index];
+            series.capacity = capacity;
+            series.drop_old_points();
+        }
+        self.redraw();
+    }
+
+    pub fn series_capacity(&self, index: usize) -> Option<usize> {
+        self.data.borrow().series[index].capacity
+    }
+
+    /// If set, whenever points are appended the x range is scrolled to show the given width up to
+    /// the largest x value of all series. This keeps the newest points of streaming data in
+    /// view.
+    pub fn set_x_scroll_window(&self, width: Option<f64>) {
+        {
+            let mut data = self.data.borrow_mut();
+            data.x_scroll_window = width;
+            data.scroll_x();
+        }
+        self.redraw();
+    }
+
+    pub fn x_scroll_window(&self) -> Option<f64> {
+        self.data.borrow().x_scroll_window
+    }
+
+    /// If true, which is the default, pyramids for large series are built on another thread, and
+    /// series are drawn from all their points until their pyramid is ready. If false, pyramids
+    /// are built when points are set, which blocks but means drawing is always fast.
+    ///
+    /// A pyramid is a series of copies of the points, each with half as many points as the
+    /// one before it. Drawing uses the copy that has about as many points as there are pixels, so
+    /// that drawing a series with millions of points takes the same time as one with a
+    /// thousand.
+    pub fn set_build_pyramids_in_background(&self, background: bool) {
+        self.data.borrow_mut().build_pyramids_in_background = background;
+    }
+
+    /// Returns true if every series that is large enough to need a pyramid has one.
+    pub fn pyramids_ready(&self) -> bool {
+        self.data.borrow().pending_pyramids.is_empty()
+    }
+
+    // Builds the pyramid for a series whose points were replaced.
+    fn start_pyramid(&self, index: usize) {
+        let (points, generation) = {
+            let mut data = self.data.borrow_mut();
+            let generation = data.next_generation;
+            data.next_generation += 1;
+            let background = data.build_pyramids_in_background;
+            let series = &mut data.series[index];
+            series.generation = generation;
+            series.pyramid_pending = false;
+            series.appended_since_build.clear();
+            if series.points().len() < MIN_PYRAMID_LEVEL_SIZE * 2 {
+                return;
+            }
+            if !background {
+                series.pyramid = build_pyramid(series.points());
+                return;
+            }
+            series.pyramid_pending = true;
+            (series.points().to_vec(), generation)
+        };
+
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(build_pyramid(&points));
+        });
+
+        let mut data = self.data.borrow_mut();
+        data.pending_pyramids.push((generation, receiver));
+        if data.pyramid_timer.is_none() {
+            let timer = Timer::new();
+            timer.set_time(PYRAMID_POLL_TIME);
+            let weak_data = Rc::downgrade(&self.data);
+            let ih = self.canvas.handle();
+            timer.action_event().add(move || {
+                if let Some(data) = weak_data.upgrade() {
+                    unsafe { LineGraph::from_parts(ih, data) }.receive_pyramids();
+                }
+            });
+            data.pyramid_timer = Some(timer);
+        }
+        data.pyramid_timer.as_ref().unwrap().set_running(true);
+    }
+
+    // Swaps in any pyramids that have finished building.
+    fn receive_pyramids(&self) {
+        let (received, rebuild) = {
+            let mut data = self.data.borrow_mut();
+            let data = &mut *data;
+            let mut received = false;
+            let mut rebuild = vec![];
+            let mut i = 0;
+            while i < data.pending_pyramids.len() {
+                let result = data.pending_pyramids[i].1.try_recv();
+                match result {
+                    Ok(pyramid) => {
+                        let generation = data.pending_pyramids[i].0;
+                        // If the points were replaced or the series removed while the pyramid was
+                        // being built, it isn't used.
+                        if let Some(index) = data.series.iter().position(|s| s.generation == generation) {
+                            let series = &mut data.series[index];
+                            series.pyramid = pyramid;
+                            series.pyramid_pending = false;
+                            let appended = mem::replace(&mut series.appended_since_build, vec![]);
+                            if series.extend_pyramid(&appended) {
+                                series.trim_pyramid();
+                            } else {
+                                series.pyramid.clear();
+                                rebuild.push(index);
+                            }
+                            received = true;
+                        }
+                        data.pending_pyramids.remove(i);
+                    },
+                    // The thread panicked.
+                    Err(TryRecvError::Disconnected) => {
+                        let generation = data.pending_pyramids[i].0;
+                        if let Some(series) = data.series.iter_mut().find(|s| s.generation == generation) {
+                            series.pyramid_pending = false;
+                            series.appended_since_build.clear();
+                        }
+                        data.pending_pyramids.remove(i);
+                    },
+                    Err(TryRecvError::Empty) => i += 1,
+                }
+            }
+            if data.pending_pyramids.is_empty() {
+                if let Some(ref timer) = data.pyramid_timer {
+                    timer.set_running(false);
+                }
+            }
+            (received, rebuild)
+        };
+        for index in rebuild {
+            self.start_pyramid(index);
+        }
+        if received {
+            self.redraw();
+        }
+    }
+
+    /// Returns a copy of the points of a series, sorted by x.
+    pub fn series_points(&self, index: usize) -> Vec<DataPoint> {
+        self.data.borrow().series[index].points().to_vec()
+    }
+
+    /// Returns the range of x values that is shown.
+    pub fn x_range(&self) -> Range<f64> {
+        self.data.borrow().x_axis.clone()
+    }
+
+    pub fn set_x_range(&self, range: Range<f64>) {
+        self.data.borrow_mut().x_axis = range;
+        self.redraw();
+    }
+
+    /// Returns the range of y values that is shown on the first y axis.
+    pub fn y_range(&self) -> Range<f64> {
+        self.y_axis_range(0)
+    }
+
+    pub fn set_y_range(&self, range: Range<f64>) {
+        self.set_y_axis_range(0, range);
+    }
+
+    /// Adds a y axis and returns its index. The graph starts with one axis on the left, which
+    /// has index 0. Series are assigned to an axis with `set_series_axis`.
+    ///
+    /// Axes on the same side are drawn side by side, with the ones added first closest to the
+    /// plot.
+    pub fn add_y_axis(&self, side: YAxisSide) -> usize {
+        let index = {
+            let mut data = self.data.borrow_mut();
+            assert!(data.y_axes.len() <= u8::MAX as usize, "too many y axes");
+            data.y_axes.push(YAxis::new(side));
+            data.y_axes.len() - 1
+        };
+        self.redraw();
+        index
+    }
+
+    pub fn y_axis_count(&self) -> usize {
+        self.data.borrow().y_axes.len()
+    }
+
+    pub fn y_axis_side(&self, axis: usize) -> YAxisSide {
+        self.data.borrow().y_axes[axis].side
+    }
+
+    pub fn set_y_axis_side(&self, axis: usize, side: YAxisSide) {
+        self.data.borrow_mut().y_axes[axis].side = side;
+        self.redraw();
+    }
+
+    /// Returns the range of y values that is shown on an axis.
+    pub fn y_axis_range(&self, axis: usize) -> Range<f64> {
+        self.data.borrow().y_axes[axis].range.clone()
+    }
+
+    pub fn set_y_axis_range(&self, axis: usize, range: Range<f64>) {
+        self.data.borrow_mut().y_axes[axis].range = range;
+        self.redraw();
+    }
+
+    pub fn y_axis_title(&self, axis: usize) -> String {
+        self.data.borrow().y_axes[axis].title.clone()
+    }
+
+    /// Sets the text shown above an axis, which is usually the name and units of the values.
+    pub fn set_y_axis_title(&self, axis: usize, title: &str) {
+        self.data.borrow_mut().y_axes[axis].title = title.to_owned();
+        self.redraw();
+    }
+
+    /// Returns the index of the y axis a series is scaled by.
+    pub fn series_axis(&self, index: usize) -> usize {
+        self.data.borrow().series[index].axis as usize
+    }
+
+    pub fn set_series_axis(&self, index: usize, axis: usize) {
+        {
+            let mut data = self.data.borrow_mut();
+            assert!(axis < data.y_axes.len(), "y axis index out of bounds");
+            data.series[index].axis = axis as u8;
+        }
+        self.redraw();
+    }
+
+    pub fn series_name(&self, index: usize) -> String {
+        self.data.borrow().series[index].name.clone()
+    }
+
+    /// Sets the name of a series shown in the legend. If a series has no name, the legend
+    /// shows "Series 1", "Series 2" and so on.
+    pub fn set_series_name(&self, index: usize, name: &str) {
+        self.data.borrow_mut().series[index].name = name.to_owned();
+        self.redraw();
+    }
+
+    pub fn series_color(&self, index: usize) -> Color {
+        self.data.borrow().series[index].color
+    }
+
+    pub fn set_series_color(&self, index: usize, color: Color) {
+        self.data.borrow_mut().series[index].color = color;
+        self.redraw();
+    }
+
+    /// Returns the width of a series' line in pixels.
+    pub fn series_width(&self, index: usize) -> u32 {
+        self.data.borrow().series[index].width
+    }
+
+    pub fn set_series_width(&self, index: usize, width: u32) {
+        self.data.borrow_mut().series[index].width = width.max(1);
+        self.redraw();
+    }
+
+    pub fn series_line_style(&self, index: usize) -> LineStyle {
+        self.data.borrow().series[index].line_style
+    }
+
+    pub fn set_series_line_style(&self, index: usize, style: LineStyle) {
+        self.data.borrow_mut().series[index].line_style = style;
+        self.redraw();
+    }
+
+    pub fn series_visible(&self, index: usize) -> bool {
+        self.data.borrow().series[index].visible
+    }
+
+    /// Hides or shows a series. Hidden series are still listed in the legend, but are not drawn
+    /// or used for autoscaling.
+    pub fn set_series_visible(&self, index: usize, visible: bool) {
+        self.data.borrow_mut().series[index].visible = visible;
+        self.redraw();
+    }
+
+    pub fn legend_visible(&self) -> bool {
+        self.data.borrow().legend_visible
+    }
+
+    /// Shows or hides the legend in the top right of the plot. Clicking a series in the legend
+    /// hides or shows it. The legend is hidden by default.
+    pub fn set_legend_visible(&self, visible: bool) {
+        self.data.borrow_mut().legend_visible = visible;
+        self.redraw();
+    }
+
+    /// Sets the x range to fit all points of all visible series.
     pub fn autoscale_x(&self) {
-        let mut data = self.0.borrow_mut();
-        let (mut min_x, mut max_x) = (None, None);
-        for series in data.series.iter() {
-            if let Some(pt) = series.points.first() {
-                min_x = Some(min_x.map_or(pt.x, |mx| pt.x.min(mx)));
+        {
+            let mut data = self.data.borrow_mut();
+            let (mut min_x, mut max_x) = (None, None);
+            for series in data.series.iter().filter(|s| s.visible) {
+                if let Some(pt) = series.points().first() {
+                    min_x = Some(min_x.map_or(pt.x, |mx| pt.x.min(mx)));
+                }
+                if let Some(pt) = series.points().last() {
+                    max_x = Some(max_x.map_or(pt.x, |mx| pt.x.max(mx)));
+                }
+            }
+            if let (Some(min_x), Some(max_x)) = (min_x, max_x) {
+                data.x_axis = widen_empty_range(min_x..max_x);
+            }
+        }
+        self.redraw();
+    }
+
+    /// Sets the range of every y axis to fit the points of its visible series that are within the
+    /// x range.
+    pub fn autoscale_y(&self) {
+        {
+            let mut data = self.data.borrow_mut();
+            let data = &mut *data;
+            for (axis_index, axis) in data.y_axes.iter_mut().enumerate() {
+                let (mut min_y, mut max_y) = (None, None);
+                for series in data.series.iter().filter(|s| s.visible && s.axis as usize == axis_index) {
+                    let points = visible_points(series.points(), &data.x_axis);
+                    for pt in points.iter().filter(|pt| !pt.y.is_nan()) {
+                        min_y = Some(min_y.map_or(pt.y, |my: f64| pt.y.min(my)));
+                        max_y = Some(max_y.map_or(pt.y, |my: f64| pt.y.max(my)));
+                    }
+                }
+                if let (Some(min_y), Some(max_y)) = (min_y, max_y) {
+                    axis.range = widen_empty_range(min_y..max_y);
+                }
+            }
+        }
+        self.redraw();
+    }
+
+    /// Sets the x range and the ranges of all y axes to fit all points of all visible series.
+    pub fn autoscale_all(&self) {
+        self.autoscale_x();
+        self.autoscale_y();
+    }
+
+    /// Writes the points of all series as CSV with a header row. Series are named the same as in
+    /// the legend, and hidden series are included.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use clear_coat::*;
+    /// # use std::fs::File;
+    /// # let graph = LineGraph::new();
+    /// let mut file = File::create("graph.csv").expect("could not create file");
+    /// graph.write_csv(&mut file, CsvLayout::Aligned).expect("could not write file");
+    /// ```
+    pub fn write_csv<W: Write>(&self, writer: &mut W, layout: CsvLayout) -> io::Result<()> {
+        let data = self.data.borrow();
+        let names: Vec<String> = data.series_names().iter().map(|name| csv_field(name)).collect();
+        match layout {
+            CsvLayout::Aligned => {
+                try!(write!(writer, "x"));
+                for name in names.iter() {
+                    try!(write!(writer, ",{}", name));
+                }
+                try!(write!(writer, "\r\n"));
+
+                // Merge the sorted series, writing a row for the smallest x value left each time.
+                let mut positions = vec![0; data.series.len()];
+                loop {
+                    let x = data.series.iter().zip(positions.iter())
+                                .filter_map(|(series, &pos)| series.points().get(pos))
+                                .map(|pt| pt.x)
+                                .fold(f64::INFINITY, f64::min);
+                    if x == f64::INFINITY {
+                        break;
+                    }
+                    try!(write!(writer, "{}", x));
+                    for (series, pos) in data.series.iter().zip(positions.iter_mut()) {
+                        match series.points().get(*pos) {
+                            Some(pt) if pt.x == x => {
+                                try!(write!(writer, ",{}", csv_number(pt.y)));
+                                *pos += 1;
+                            },
+                            _ => try!(write!(writer, ",")),
+                        }
+                    }
+                    try!(write!(writer, "\r\n"));
+                }
+            },
+            CsvLayout::Long => {
+                try!(write!(writer, "series,x,y\r\n"));
+                for (series, name) in data.series.iter().zip(names.iter()) {
+                    for pt in series.points() {
+                        try!(write!(writer, "{},{},{}\r\n", name, csv_number(pt.x), csv_number(pt.y)));
+                    }
+                }
+            },
+        }
+        Ok(())
+    }
+
+    /// Draws the graph at the specified size and returns the RGBA pixels, left to right and top to
+    /// bottom. The size doesn't need to match the canvas, and the canvas doesn't need to be
+    /// shown. The crosshair and zoom rectangle aren't drawn.
+    pub fn render_rgba(&self, width: u32, height: u32) -> Vec<u8> {
+        self.data.borrow().render(width.max(1), height.max(1)).into_pixels()
+    }
+
+    /// Draws the graph at the specified size like `render_rgba` and writes it as a PNG image.
+    #[cfg(feature = "save-image")]
+    pub fn write_png<W: Write>(&self, writer: &mut W, width: u32, height: u32) -> io::Result<()> {
+        use png::HasParameters;
+        let (width, height) = (width.max(1), height.max(1));
+        let mut encoder = png::Encoder::new(writer, width, height);
+        encoder.set(png::ColorType::RGBA).set(png::BitDepth::Eight);
+        let mut png_writer = try!(encoder.write_header());
+        try!(png_writer.write_image_data(&self.render_rgba(width, height)));
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+pub struct LineGraphHoverArgs {
+    /// The position of the mouse in graph coordinates using the first y axis, or `None` if the
+    /// mouse left the graph.
+    pub position: Option<DataPoint>,
+    /// The point nearest the mouse horizontally of each series, in the same order as the series,
+    /// or `None` for an empty or hidden series.
+    pub nearest: Vec<Option<DataPoint>>,
+    _dummy: (),
+}
+
+// The hover event isn't an IUP callback, so this registry's callback is only used to store the
+// callbacks. The event is notified by `LineGraph::set_hover`.
+callback_token!(LineGraphHoverToken);
+thread_local!(
+    static HOVER_CALLBACKS: CallbackRegistry<FnMut(&LineGraphHoverArgs), LineGraphHoverToken> =
+        CallbackRegistry::new("_CLEAR_COAT_LINE_GRAPH_HOVER_CB\0", unsafe { mem::transmute::<_, Icallback>(hover_cb as usize) })
+);
+extern fn hover_cb(_ih: *mut Ihandle) -> c_int {
+    IUP_DEFAULT
+}
+
+unsafe impl Control for LineGraph {
+    fn handle(&self) -> *mut Ihandle {
+        self.canvas.handle()
+    }
+}
+
+impl ExpandAttribute for LineGraph {}
+impl MinMaxSizeAttribute for LineGraph {}
+impl TipAttribute for LineGraph {}
+impl VisibleAttribute for LineGraph {}
+
+// A point without a finite x value has no place in a sorted series, so it would break sorting,
+// merging series, finding points by x, and the pyramid's buckets.
+fn remove_non_finite_x(points: &mut Vec<DataPoint>) {
+    points.retain(|pt| pt.x.is_finite());
+}
+
+fn sort_points(points: &mut [DataPoint]) {
+    points.sort_by(|a, b| a.x.partial_cmp(&b.x).unwrap_or(Ordering::Equal));
+}
+
+// A range where the start and end are equal can't be drawn, so it is widened to be centered on
+// the value.
+fn widen_empty_range(range: Range<f64>) -> Range<f64> {
+    if range.end > range.start {
+        range
+    } else {
+        let half = if range.start == 0.0 { 0.5 } else { range.start.abs() * 0.05 };
+        (range.start - half)..(range.start + half)
+    }
+}
+
+// Returns the index of the first point with an x value that is not less than `x`.
+fn lower_bound(points: &[DataPoint], x: f64) -> usize {
+    let (mut low, mut high) = (0, points.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if points[mid].x < x {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+// Returns the index of the first point with an x value that is greater than `x`.
+fn upper_bound(points: &[DataPoint], x: f64) -> usize {
+    let (mut low, mut high) = (0, points.len());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if points[mid].x <= x {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+// Builds all the pyramid levels for the points. Returns no levels if there are too few points for
+// a level to be useful.
+fn build_pyramid(points: &[DataPoint]) -> Vec<PyramidLevel> {
+    let mut levels = vec![];
+    let (first_x, last_x) = match (points.first(), points.last()) {
+        (Some(first), Some(last)) => (first.x, last.x),
+        _ => return levels,
+    };
+    if !(last_x > first_x) || !(last_x - first_x).is_finite() || points.len() < MIN_PYRAMID_LEVEL_SIZE * 2 {
+        return levels;
+    }
+
+    // Start with about two points per bucket and round the step up to a power of two.
+    let rough_step = (last_x - first_x) / (points.len() / 2) as f64;
+    let x_step = 2f64.powf(rough_step.log2().ceil());
+    let min_x = (first_x / x_step).floor() * x_step;
+    let count = ((last_x - min_x) / x_step) as usize + 1;
+    let mut merged_points = vec![MergedDataPoint::empty(); count];
+    for pt in points.iter().filter(|pt| !pt.y.is_nan()) {
+        let i = (((pt.x - min_x) / x_step) as usize).min(count - 1);
+        merged_points[i].add(&MergedDataPoint { min_y: pt.y, max_y: pt.y });
+    }
+    let mut level = PyramidLevel { min_x: min_x, x_step: x_step, merged_points: merged_points };
+
+    while level.merged_points.len() >= MIN_PYRAMID_LEVEL_SIZE {
+        let next = merge_pyramid_level(&level);
+        levels.push(level);
+        level = next;
+    }
+    levels
+}
+
+// Returns a level with twice the `x_step` of `level`.
+fn merge_pyramid_level(level: &PyramidLevel) -> PyramidLevel {
+    let x_step = level.x_step * 2.0;
+    let min_x = (level.min_x / x_step).floor() * x_step;
+    // Since `min_x` is aligned to the new step, the first bucket of `level` is either the first
+    // or second half of the first new bucket.
+    let offset = ((level.min_x - min_x) / level.x_step).round() as usize;
+    let count = (level.merged_points.len() + offset + 1) / 2;
+    let mut merged_points = vec![MergedDataPoint::empty(); count];
+    for (i, pt) in level.merged_points.iter().enumerate() {
+        merged_points[(i + offset) / 2].add(pt);
+    }
+    PyramidLevel { min_x: min_x, x_step: x_step, merged_points: merged_points }
+}
+
+// Returns the level with the fewest points that still has at least one point per `pixel_step`
+// or `None` if the raw points should be used.
+fn choose_pyramid_level(pyramid: &[PyramidLevel], pixel_step: f64) -> Option<&PyramidLevel> {
+    pyramid.iter().take_while(|level| level.x_step <= pixel_step).last()
+}
+
+// Returns the point with the x value nearest to `x`.
+fn nearest_point(points: &[DataPoint], x: f64) -> Option<DataPoint> {
+    let i = lower_bound(points, x);
+    let after = points.get(i);
+    let before = if i > 0 { points.get(i - 1) } else { None };
+    match (before, after) {
+        (Some(b), Some(a)) => Some(if x - b.x <= a.x - x { *b } else { *a }),
+        (Some(p), None) | (None, Some(p)) => Some(*p),
+        (None, None) => None,
+    }
+}
+
+// Quotes a CSV field if it contains a character that has to be escaped.
+fn csv_field(text: &str) -> String {
+    if text.contains(|c| c == ',' || c == '"' || c == '\r' || c == '\n') {
+        format!("\"{}\"", text.replace("\"", "\"\""))
+    } else {
+        text.to_owned()
+    }
+}
+
+// NaN is written as an empty cell, which is how spreadsheets show missing values.
+fn csv_number(n: f64) -> String {
+    if n.is_nan() { String::new() } else { n.to_string() }
+}
+
+// Scales the range by `factor` while keeping `center` at the same position.
+fn zoom_range(range: &Range<f64>, center: f64, factor: f64) -> Range<f64> {
+    limit_zoom((center - (center - range.start) * factor)..(center + (range.end - center) * factor))
+}
+
+// Widens a range around its middle if it is smaller than `MIN_RELATIVE_SPAN` allows.
+fn limit_zoom(range: Range<f64>) -> Range<f64> {
+    let middle = range.start / 2.0 + range.end / 2.0;
+    let min_span = (middle.abs() * MIN_RELATIVE_SPAN).max(f64::MIN_POSITIVE);
+    if range.end - range.start >= min_span {
+        range
+    } else {
+        (middle - min_span / 2.0)..(middle + min_span / 2.0)
+    }
+}
+
+// Moves the range by `pixels` in a plot that is `size` pixels long, keeping its span.
+fn pan_range(range: &Range<f64>, pixels: i32, size: i32) -> Range<f64> {
+    let offset = pixels as f64 * range_span(range) / size as f64;
+    (range.start + offset)..(range.end + offset)
+}
+
+fn visible_points<'a>(points: &'a [DataPoint], x_axis: &Range<f64>) -> &'a [DataPoint] {
+    &points[lower_bound(points, x_axis.start)..upper_bound(points, x_axis.end)]
+}
+
+// Returns a step between ticks of 1, 2, or 5 times a power of ten so that there are at most
+// about `max_ticks` ticks.
+fn tick_step(range: &Range<f64>, max_ticks: i32) -> f64 {
+    let rough = (range.end - range.start) / max_ticks.max(1) as f64;
+    let magnitude = 10f64.powf(rough.log10().floor());
+    let normalized = rough / magnitude;
+    let nice = if normalized <= 1.0 {
+        1.0
+    } else if normalized <= 2.0 {
+        2.0
+    } else if normalized <= 5.0 {
+        5.0
+    } else {
+        10.0
+    };
+    nice * magnitude
+}
+
+fn ticks(range: &Range<f64>, max_ticks: i32) -> (Vec<f64>, f64) {
+    let step = tick_step(range, max_ticks);
+    let mut ticks = vec![];
+    if !step.is_finite() || step <= 0.0 {
+        return (ticks, step);
+    }
+    let first = (range.start / step).ceil();
+    // If the step is too small compared to the values, adding it doesn't change them, so there
+    // is nowhere to put the ticks.
+    if first + 1.0 == first || step < range.start.abs() * f64::EPSILON * 4.0 {
+        return (ticks, step);
+    }
+    // The step is at least the range divided by `max_ticks`, so this many is always enough.
+    for k in 0..max_ticks.max(1) + 2 {
+        let tick = (first + k as f64) * step;
+        // Allow for rounding error so that a tick at the end of the range isn't skipped.
+        if tick > range.end + step * 1e-9 {
+            break;
+        }
+        ticks.push(tick);
+    }
+    (ticks, step)
+}
+
+fn format_tick(value: f64, step: f64) -> String {
+    // Avoid printing "-0".
+    let value = if value.abs() < step * 1e-9 { 0.0 } else { value };
+    if value != 0.0 && (value.abs() >= 1e7 || step < 1e-6) {
+        format!("{:e}", value)
+    } else {
+        let decimals = (-step.log10().floor()).max(0.0) as usize;
+        format!("{:.*}", decimals, value)
+    }
+}
+
+fn range_span(range: &Range<f64>) -> f64 {
+    let span = range.end - range.start;
+    if span > 0.0 { span } else { 1.0 }
+}
+
+impl LineGraphData {
+    fn scroll_x(&mut self) {
+        let width = match self.x_scroll_window {
+            Some(width) => width,
+            None => return,
+        };
+        let max_x = self.series.iter().filter_map(|s| s.points().last()).map(|pt| pt.x)
+                               .fold(f64::NEG_INFINITY, f64::max);
+        if max_x.is_finite() {
+            self.x_axis = widen_empty_range((max_x - width)..max_x);
+        }
+    }
+
+    fn pixel_to_x(&self, plot: Rect, px: f64) -> f64 {
+        self.x_axis.start + (px - plot.x as f64) / plot.width as f64 * range_span(&self.x_axis)
+    }
+
+    fn pixel_to_y(&self, axis: usize, plot: Rect, py: f64) -> f64 {
+        let y_axis = &self.y_axes[axis].range;
+        y_axis.start + ((plot.y + plot.height) as f64 - py) / plot.height as f64 * range_span(y_axis)
+    }
+
+    fn x_to_pixel(&self, plot: Rect, x: f64) -> f64 {
+        plot.x as f64 + (x - self.x_axis.start) / range_span(&self.x_axis) * plot.width as f64
+    }
+
+    fn y_to_pixel(&self, axis: usize, plot: Rect, y: f64) -> f64 {
+        let y_axis = &self.y_axes[axis].range;
+        (plot.y + plot.height) as f64 - (y - y_axis.start) / range_span(y_axis) * plot.height as f64
+    }
+
+    // Draws the parts of the graph that are only shown on screen: the crosshair and the rubber
+    // band for zooming.
+    fn draw_overlay(&self, painter: &mut Painter, plot: Rect) {
+        painter.set_clip(Some(plot));
+        if let Some((hx, hy)) = self.hover {
+            painter.fill_rect(Rect::new(hx, plot.y, 1, plot.height), CROSSHAIR_COLOR);
+            painter.fill_rect(Rect::new(plot.x, hy, plot.width, 1), CROSSHAIR_COLOR);
+            let x = self.pixel_to_x(plot, hx as f64);
+            for series in self.series.iter().filter(|s| s.visible) {
+                if let Some(pt) = nearest_point(series.points(), x) {
+                    let px = self.x_to_pixel(plot, pt.x).round() as i32;
+                    let py = self.y_to_pixel(series.axis as usize, plot, pt.y).round() as i32;
+                    painter.rect(Rect::new(px - 3, py - 3, 7, 7), series.color);
+                }
             }
-            if let Some(pt) = series.points.last() {
-                max_x = Some(max_x.map_or(pt.x, |mx| pt.x.max(mx)));
+        }
+        if let Some(Drag::Zoom(start, end)) = self.drag {
+            let (x0, x1) = (start.0.min(end.0), start.0.max(end.0));
+            let (y0, y1) = (start.1.min(end.1), start.1.max(end.1));
+            painter.rect(Rect::new(x0, y0, x1 - x0 + 1, y1 - y0 + 1), ZOOM_RECT_COLOR);
+        }
+        painter.set_clip(None);
+    }
+
+    // Returns where the parts of the graph go when it is drawn at the specified size with the
+    // text size of `painter`.
+    fn layout(&self, width: i32, height: i32, painter: &Painter) -> Layout {
+        let text_height = painter.text_size("0").1;
+        let top = if self.y_axes.iter().any(|axis| !axis.title.is_empty()) {
+            OUTER_MARGIN + text_height + LABEL_PADDING
+        } else {
+            OUTER_MARGIN
+        };
+        let bottom = text_height + TICK_LENGTH + LABEL_PADDING * 2;
+        let plot_height = (height - top - bottom).max(1);
+
+        let (mut left, mut right) = (0, 0);
+        for axis in self.y_axes.iter() {
+            let axis_width = y_axis_width(&axis.range, plot_height, painter);
+            match axis.side {
+                YAxisSide::Left => left += axis_width,
+                YAxisSide::Right => right += axis_width,
             }
         }
-        if let (Some(min_x), Some(max_x)) = (min_x, max_x) {
-            data.x_axis = min_x..max_x;
+        let right = right.max(OUTER_MARGIN);
+        let plot = Rect::new(left, top, (width - left - right).max(1), plot_height);
+
+        let legend = if self.legend_visible {
+            let names = self.series_names();
+            let text_width = names.iter().map(|name| painter.text_size(name).0).max().unwrap_or(0);
+            let entry_width = LABEL_PADDING * 3 + LEGEND_SAMPLE_LENGTH + text_width;
+            let entry_height = text_height + LABEL_PADDING;
+            let x = plot.x + plot.width - LABEL_PADDING - entry_width;
+            let y = plot.y + LABEL_PADDING;
+            (0..names.len()).map(|i| {
+                Rect::new(x, y + LABEL_PADDING / 2 + i as i32 * entry_height, entry_width, entry_height)
+            }).collect()
+        } else {
+            vec![]
+        };
+        Layout { plot: plot, legend: legend }
+    }
+
+    fn series_names(&self) -> Vec<String> {
+        self.series.iter().enumerate().map(|(i, series)| {
+            if series.name.is_empty() { format!("Series {}", i + 1) } else { series.name.clone() }
+        }).collect()
+    }
+
+    fn render(&self, width: u32, height: u32) -> Raster {
+        let mut raster = Raster::new(width, height, BACKGROUND_COLOR);
+        let layout = self.layout(width as i32, height as i32, &raster);
+        self.draw_plot(&mut raster, layout.plot);
+        self.draw_axes(&mut raster, &layout);
+        self.draw_legend(&mut raster, &layout);
+        raster
+    }
+
+    // Draws the grid and the series inside the plot area. This is the slow part of drawing, so it
+    // is kept separate from the text and the overlay.
+    fn draw_plot(&self, raster: &mut Raster, plot: Rect) {
+        let bottom = plot.y + plot.height - 1;
+        let x_span = range_span(&self.x_axis);
+        let (x_ticks, _) = ticks(&self.x_axis, plot.width / 80);
+        for &x in x_ticks.iter() {
+            let px = plot.x + ((x - self.x_axis.start) / x_span * plot.width as f64) as i32;
+            raster.fill_rect(px, plot.y, 1, plot.height, GRID_COLOR);
+        }
+        // Only the first axis has grid lines so that the plot isn't cluttered.
+        if let Some(axis) = self.y_axes.first() {
+            let y_span = range_span(&axis.range);
+            let (y_ticks, _) = ticks(&axis.range, plot.height / 40);
+            for &y in y_ticks.iter() {
+                let py = bottom - ((y - axis.range.start) / y_span * plot.height as f64) as i32;
+                raster.fill_rect(plot.x, py, plot.width, 1, GRID_COLOR);
+            }
+        }
+
+        raster.set_clip(Some(plot));
+        for series in self.series.iter().filter(|s| s.visible) {
+            let y_axis = &self.y_axes[series.axis as usize].range;
+            let pixel_step = range_span(&self.x_axis) / plot.width as f64;
+            if let Some(level) = choose_pyramid_level(&series.pyramid, pixel_step) {
+                // Include the buckets just outside the x range so that lines are drawn to the
+                // edges.
+                let len = level.merged_points.len();
+                // Buckets before the first point may hold points that were dropped.
+                let first_x = series.points().first().map_or(level.min_x, |pt| pt.x);
+                let first = ((first_x - level.min_x) / level.x_step).floor();
+                let start = (((self.x_axis.start - level.min_x) / level.x_step).floor() - 1.0).max(first);
+                let end = ((self.x_axis.end - level.min_x) / level.x_step).ceil() + 1.0;
+                let start = start.max(0.0).min(len as f64) as usize;
+                let end = end.max(0.0).min(len as f64) as usize;
+                let samples = level.merged_points[start..end].iter().enumerate()
+                    .filter(|&(_, pt)| !pt.is_empty())
+                    .map(|(i, pt)| {
+                        let x = level.min_x + ((start + i) as f64 + 0.5) * level.x_step;
+                        (x, pt.min_y, pt.max_y)
+                    });
+                draw_samples(raster, plot, &self.x_axis, y_axis, samples, series);
+            } else {
+                let points = series.points();
+                // Include the points just outside the x range so that lines are drawn to the
+                // edges.
+                let start = lower_bound(points, self.x_axis.start).saturating_sub(1);
+                let end = (upper_bound(points, self.x_axis.end) + 1).min(points.len());
+                let samples = points[start..end].iter().map(|pt| (pt.x, pt.y, pt.y));
+                draw_samples(raster, plot, &self.x_axis, y_axis, samples, series);
+            }
         }
+        raster.set_clip(None);
     }
 
-    pub fn autoscale_all() {
+    fn draw_legend(&self, painter: &mut Painter, layout: &Layout) {
+        let (first, last) = match (layout.legend.first(), layout.legend.last()) {
+            (Some(first), Some(last)) => (*first, *last),
+            _ => return,
+        };
+        painter.set_clip(Some(layout.plot));
+        let height = last.y + last.height - first.y + LABEL_PADDING / 2;
+        let frame = Rect::new(first.x, first.y - LABEL_PADDING / 2, first.width, height);
+        painter.fill_rect(frame, BACKGROUND_COLOR);
+        painter.rect(frame, AXIS_COLOR);
+        let text_height = painter.text_size("0").1;
+        for ((entry, series), name) in layout.legend.iter().zip(self.series.iter()).zip(self.series_names()) {
+            let (color, text_color) = if series.visible {
+                (series.color, TEXT_COLOR)
+            } else {
+                (HIDDEN_SERIES_COLOR, HIDDEN_SERIES_COLOR)
+            };
+            let line_x = entry.x + LABEL_PADDING;
+            let line_y = entry.y + entry.height / 2;
+            painter.line(line_x, line_y, line_x + LEGEND_SAMPLE_LENGTH, line_y,
+                         series.width, series.line_style, color);
+            let text_x = line_x + LEGEND_SAMPLE_LENGTH + LABEL_PADDING;
+            painter.text(text_x, entry.y + (entry.height - text_height) / 2, &name, text_color);
+        }
+        painter.set_clip(None);
+    }
+
+    fn draw_axes(&self, painter: &mut Painter, layout: &Layout) {
+        let plot = layout.plot;
+        let (left, top) = (plot.x, plot.y);
+        let (right, bottom) = (plot.x + plot.width - 1, plot.y + plot.height - 1);
+        let text_height = painter.text_size("0").1;
+
+        let x_span = range_span(&self.x_axis);
+        let (x_ticks, x_step) = ticks(&self.x_axis, plot.width / 80);
+        for &x in x_ticks.iter() {
+            let px = left + ((x - self.x_axis.start) / x_span * plot.width as f64) as i32;
+            painter.fill_rect(Rect::new(px, bottom + 1, 1, TICK_LENGTH), AXIS_COLOR);
+            let label = format_tick(x, x_step);
+            let label_x = px - painter.text_size(&label).0 / 2;
+            painter.text(label_x, bottom + 1 + TICK_LENGTH + LABEL_PADDING, &label, TEXT_COLOR);
+        }
+
+        let (mut left_offset, mut right_offset) = (0, 0);
+        for axis in self.y_axes.iter() {
+            let axis_width = y_axis_width(&axis.range, plot.height, painter);
+            // The x coordinate of the axis line and the direction away from the plot
+            let (axis_x, dir) = match axis.side {
+                YAxisSide::Left => {
+                    left_offset += axis_width;
+                    (left - 1 - (left_offset - axis_width), -1)
+                },
+                YAxisSide::Right => {
+                    right_offset += axis_width;
+                    (right + 1 + (right_offset - axis_width), 1)
+                },
+            };
+            painter.line(axis_x, top - 1, axis_x, bottom + 1, 1, LineStyle::Solid, AXIS_COLOR);
+
+            let y_span = range_span(&axis.range);
+            let (y_ticks, y_step) = ticks(&axis.range, plot.height / 40);
+            for &y in y_ticks.iter() {
+                let py = bottom - ((y - axis.range.start) / y_span * plot.height as f64) as i32;
+                let tick_x = if dir < 0 { axis_x - TICK_LENGTH } else { axis_x + 1 };
+                painter.fill_rect(Rect::new(tick_x, py, TICK_LENGTH, 1), AXIS_COLOR);
+                let label = format_tick(y, y_step);
+                let label_x = if dir < 0 {
+                    axis_x - TICK_LENGTH - LABEL_PADDING - painter.text_size(&label).0
+                } else {
+                    axis_x + 1 + TICK_LENGTH + LABEL_PADDING
+                };
+                painter.text(label_x, py - text_height / 2, &label, TEXT_COLOR);
+            }
+
+            if !axis.title.is_empty() {
+                let title_width = painter.text_size(&axis.title).0;
+                let title_x = if dir < 0 { axis_x - title_width } else { axis_x + 1 };
+                painter.text(title_x.max(0), top - 1 - LABEL_PADDING - text_height, &axis.title, TEXT_COLOR);
+            }
+        }
+
+        painter.rect(Rect::new(left - 1, top - 1, right - left + 3, bottom - top + 3), AXIS_COLOR);
+    }
+}
+
+// The y values of the samples drawn in one pixel column, in pixel coordinates.
+struct Column {
+    px: f64,
+    first: f64,
+    last: f64,
+    min: f64,
+    max: f64,
+}
+
+// Draws samples of (x, min y, max y) sorted by x. This is the min/max-per-pixel approach: all the
+// samples in a pixel column are reduced to a vertical line from the minimum to the maximum, and
+// adjacent columns are connected by a line from the last value in one to the first in the next.
+// Drawing time depends on the number of samples passed in, not on the number of pixels.
+fn draw_samples<I>(raster: &mut Raster, plot: Rect, x_axis: &Range<f64>, y_axis: &Range<f64>,
+                   samples: I, series: &DataSeries)
+                   where I: Iterator<Item=(f64, f64, f64)> {
+    // The pattern continues from one line to the next so that dashes are even.
+    let mut counter = 0;
+    let mut pattern = |_| {
+        counter += 1;
+        series.line_style.is_on(counter - 1)
+    };
+    let mut draw_line = |raster: &mut Raster, x0, y0, x1, y1| {
+        raster.draw_line_pattern(x0, y0, x1, y1, series.width, series.color, &mut pattern);
+    };
+
+    let x_scale = plot.width as f64 / range_span(x_axis);
+    let y_scale = plot.height as f64 / range_span(y_axis);
+    let plot_bottom = (plot.y + plot.height) as f64;
+
+    let mut prev: Option<Column> = None;
+    let mut current: Option<Column> = None;
+    for (x, min_y, max_y) in samples {
+        if min_y.is_nan() || max_y.is_nan() {
+            continue;
+        }
+        // Pixel columns are found by flooring, and lines are drawn through the center of them.
+        let px = (plot.x as f64 + (x - x_axis.start) * x_scale).floor() + 0.5;
+        let low = plot_bottom - (min_y - y_axis.start) * y_scale;
+        let high = plot_bottom - (max_y - y_axis.start) * y_scale;
+
+        let same_column = current.as_ref().map_or(false, |c| c.px == px);
+        if same_column {
+            let c = current.as_mut().unwrap();
+            // Leave the column at whichever end of the sample is farther from where it entered.
+            let (near, far) = if (low - c.last).abs() <= (high - c.last).abs() { (low, high) } else { (high, low) };
+            c.min = c.min.min(near).min(far);
+            c.max = c.max.max(near).max(far);
+            c.last = far;
+        } else {
+            let (first, last) = match current.as_ref() {
+                Some(c) if (high - c.last).abs() < (low - c.last).abs() => (high, low),
+                _ => (low, high),
+            };
+            if let Some(c) = current.take() {
+                flush_column(raster, &prev, &c, &mut draw_line);
+                prev = Some(c);
+            }
+            current = Some(Column {
+                px: px,
+                first: first,
+                last: last,
+                min: low.min(high),
+                max: low.max(high),
+            });
+        }
+    }
+    if let Some(c) = current {
+        flush_column(raster, &prev, &c, &mut draw_line);
+    }
+}
+
+fn flush_column<F>(raster: &mut Raster, prev: &Option<Column>, column: &Column, draw_line: &mut F)
+                   where F: FnMut(&mut Raster, f64, f64, f64, f64) {
+    if let Some(ref p) = *prev {
+        draw_line(raster, p.px, p.last, column.px, column.first);
+    }
+    draw_line(raster, column.px, column.min, column.px, column.max);
+}
+
+// Returns the width of a y axis's ticks and labels.
+fn y_axis_width(range: &Range<f64>, plot_height: i32, painter: &Painter) -> i32 {
+    let (y_ticks, y_step) = ticks(range, plot_height / 40);
+    let label_width = y_ticks.iter()
+                             .map(|&y| painter.text_size(&format_tick(y, y_step)).0)
+                             .max()
+                             .unwrap_or(0);
+    label_width + TICK_LENGTH + LABEL_PADDING * 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ticks() {
+        let (x_ticks, step) = ticks(&(0.0..10.0), 5);
+        assert_eq!(step, 2.0);
+        assert_eq!(x_ticks, vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+
+        // Zoomed far into large values, like Unix timestamps, the step is too small to add to the
+        // values, so there are no ticks instead of an endless loop.
+        let (x_ticks, _) = ticks(&(1.7e9..1.7e9 + 1e-6), 10);
+        assert!(x_ticks.len() <= 11);
+        let (x_ticks, _) = ticks(&(1e17..1e17 + 1.0), 10);
+        assert!(x_ticks.is_empty());
+    }
+
+    #[test]
+    fn test_zoom_and_pan() {
+        // Zooming in twice around the same point halves the distance to it twice.
+        let range = zoom_range(&zoom_range(&(0.0..100.0), 20.0, 0.5), 20.0, 0.5);
+        assert_eq!(range, 15.0..40.0);
+        // Zooming out undoes it.
+        assert_eq!(zoom_range(&range, 20.0, 4.0), 0.0..100.0);
+
+        // Zooming stops at a span relative to the values instead of shrinking to nothing.
+        let mut range = 1.7e9..1.7e9 + 60.0;
+        for _ in 0..500 {
+            range = zoom_range(&range, 1.7e9 + 30.0, WHEEL_ZOOM_FACTOR);
+        }
+        assert!(range.end - range.start >= 1.7e9 * MIN_RELATIVE_SPAN * 0.99);
+        assert!(range.start <= 1.7e9 + 30.0 && range.end >= 1.7e9 + 30.0);
+        assert!(ticks(&range, 10).0.len() <= 11);
+
+        // Panning moves the range by the dragged fraction of the plot and keeps the span.
+        assert_eq!(pan_range(&(10.0..30.0), 50, 100), 20.0..40.0);
+        assert_eq!(pan_range(&(10.0..30.0), -25, 100), 5.0..25.0);
+    }
+
+    // Returns the min and max y of the points in each bucket of the level by checking every point.
+    fn scan_buckets(points: &[DataPoint], level: &PyramidLevel) -> Vec<(f64, f64)> {
+        (0..level.merged_points.len()).map(|i| {
+            let start = level.min_x + i as f64 * level.x_step;
+            points.iter()
+                  .filter(|pt| pt.x >= start && pt.x < start + level.x_step && !pt.y.is_nan())
+                  .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), pt| (min.min(pt.y), max.max(pt.y)))
+        }).collect()
+    }
+
+    #[test]
+    fn test_build_pyramid() {
+        // Too few points for a pyramid to be worth it.
+        let few: Vec<_> = (0..100).map(|i| DataPoint::new(i as f64, 0.0)).collect();
+        assert!(build_pyramid(&few).is_empty());
+
+        let mut seed = 12345u32;
+        let points: Vec<_> = (0..8000).map(|i| {
+            seed = seed.wrapping_mul(1103515245).wrapping_add(12345);
+            let y = if i % 97 == 0 { f64::NAN } else { (seed >> 16) as f64 / 100.0 };
+            DataPoint::new(3.0 + i as f64 * 0.75, y)
+        }).collect();
+        let pyramid = build_pyramid(&points);
+        assert!(pyramid.len() >= 2);
+        for (i, level) in pyramid.iter().enumerate() {
+            if i > 0 {
+                assert_eq!(level.x_step, pyramid[i - 1].x_step * 2.0);
+            }
+            assert!(level.min_x <= points[0].x);
+            assert!(level.min_x + level.merged_points.len() as f64 * level.x_step > points[points.len() - 1].x);
+            let expected = scan_buckets(&points, level);
+            for (bucket, &(min, max)) in level.merged_points.iter().zip(expected.iter()) {
+                assert_eq!((bucket.min_y, bucket.max_y), (min, max));
+            }
+        }
+        assert!(pyramid.last().unwrap().merged_points.len() >= MIN_PYRAMID_LEVEL_SIZE / 2);
+    }
+
+    #[test]
+    fn test_choose_pyramid_level() {
+        let points: Vec<_> = (0..20000).map(|i| DataPoint::new(i as f64, i as f64)).collect();
+        let pyramid = build_pyramid(&points);
+        let x_axis = 0.0..20000.0;
+        for &width in [100.0, 640.0, 1920.0, 19000.0, 40000.0].iter() {
+            let pixel_step = range_span(&x_axis) / width;
+            match choose_pyramid_level(&pyramid, pixel_step) {
+                Some(level) => {
+                    // The level has at least one bucket per pixel, and the next level doesn't.
+                    assert!(level.x_step <= pixel_step);
+                    let index = pyramid.iter().position(|l| l.x_step == level.x_step).unwrap();
+                    if let Some(next) = pyramid.get(index + 1) {
+                        assert!(next.x_step > pixel_step);
+                    }
+                },
+                // Even the first level is coarser than a pixel, so the points are drawn.
+                None => assert!(pyramid[0].x_step > pixel_step),
+            }
+        }
+        // Zoomed out, the coarsest level is used, and zoomed in, the points are.
+        assert_eq!(choose_pyramid_level(&pyramid, 1e9).unwrap().x_step, pyramid.last().unwrap().x_step);
+        assert!(choose_pyramid_level(&pyramid, 1e-3).is_none());
+    }
+
+    #[test]
+    fn test_extend_pyramid_far_past_end() {
+        let points: Vec<_> = (0..4000).map(|i| DataPoint::new(i as f64, (i % 7) as f64)).collect();
+        let mut series = DataSeries::new(points.clone(), SERIES_COLORS[0]);
+        series.pyramid = build_pyramid(&points);
+        let len = series.pyramid[0].merged_points.len();
+
+        assert!(series.extend_pyramid(&[DataPoint::new(4000.5, 1.0)]));
+        assert!(series.pyramid[0].merged_points.len() <= len + 1);
+        // A jump much larger than the points would need far more buckets than points, so the
+        // pyramid has to be rebuilt instead.
+        assert!(!series.extend_pyramid(&[DataPoint::new(1e15, 1.0)]));
+        assert!(series.pyramid[0].merged_points.len() <= len + 1);
+
+        let mut points = vec![DataPoint::new(f64::INFINITY, 1.0), DataPoint::new(1.0, 2.0),
+                              DataPoint::new(f64::NAN, 3.0), DataPoint::new(f64::NEG_INFINITY, 4.0)];
+        remove_non_finite_x(&mut points);
+        assert_eq!(points, vec![DataPoint::new(1.0, 2.0)]);
+    }
 
+    #[test]
+    fn test_nearest_point() {
+        let points = [DataPoint::new(0.0, 1.0), DataPoint::new(2.0, 2.0), DataPoint::new(5.0, 3.0)];
+        assert_eq!(nearest_point(&points, -1.0), Some(points[0]));
+        assert_eq!(nearest_point(&points, 1.1), Some(points[1]));
+        assert_eq!(nearest_point(&points, 3.4), Some(points[1]));
+        assert_eq!(nearest_point(&points, 3.6), Some(points[2]));
+        assert_eq!(nearest_point(&points, 9.0), Some(points[2]));
+        assert_eq!(nearest_point(&[], 1.0), None);
     }
 }
@@ -163,12 +163,17 @@ impl List {
 impl_control_traits!(List);
 
 impl ActiveAttribute for List {}
+impl DropFilesTargetAttribute for List {}
+impl DragDropAttribute for List {}
 impl ExpandAttribute for List {}
 impl MinMaxSizeAttribute for List {}
 impl VisibleAttribute for List {}
 impl VisibleColumnsLinesAttribute for List {}
 
 impl MenuCommonCallbacks for List {}
+impl DropFilesCallback for List {}
+impl DragSourceCallbacks for List {}
+impl DropTargetCallbacks for List {}
 
 #[derive(Clone)]
 pub struct ListActionArgs<'a> {
@@ -0,0 +1,397 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+use super::control_prelude::*;
+use std::cell::RefCell;
+use std::ffi::CStr;
+use attributes::{
+    get_str_attribute_id2,
+    reset_attribute_id2,
+    set_str_attribute_id2,
+};
+use super::{Color, HAlignment};
+use super::controls_sys::{controls_open, IupMatrixEx};
+
+/// A spreadsheet-like grid of cells. Requires the `iup-controls` feature.
+///
+/// Line and column indexes are zero-based and don't include the title line and column, which are
+/// accessed with separate methods. (IUP uses 0 for the titles and starts cells at 1.)
+///
+/// # Examples
+///
+/// ```no_run
+/// # use clear_coat::*;
+/// let matrix = Matrix::new();
+/// matrix.set_num_lines(2).set_num_columns(2);
+/// matrix.set_column_title(0, "Name");
+/// matrix.set_cell(0, 0, "Alice");
+/// ```
+#[derive(Clone)]
+pub struct Matrix(HandleRc);
+
+impl Matrix {
+    pub fn new() -> Self {
+        unsafe {
+            controls_open();
+            let ih = IupMatrixEx();
+            Matrix(HandleRc::new(ih))
+        }
+    }
+
+    /// Returns the number of lines, not including the title line.
+    pub fn num_lines(&self) -> usize {
+        unsafe {
+            let s = get_str_attribute_slice(self.handle(), "NUMLIN\0");
+            s.parse().expect("could not convert NUMLIN to an integer")
+        }
+    }
+
+    pub fn set_num_lines(&self, num: usize) -> &Self {
+        set_str_attribute(self.handle(), "NUMLIN\0", &format!("{}\0", num));
+        self
+    }
+
+    /// Returns the number of columns, not including the title column.
+    pub fn num_columns(&self) -> usize {
+        unsafe {
+            let s = get_str_attribute_slice(self.handle(), "NUMCOL\0");
+            s.parse().expect("could not convert NUMCOL to an integer")
+        }
+    }
+
+    pub fn set_num_columns(&self, num: usize) -> &Self {
+        set_str_attribute(self.handle(), "NUMCOL\0", &format!("{}\0", num));
+        self
+    }
+
+    /// Sets the number of lines that are visible when the matrix is first shown, which
+    /// determines its natural height.
+    pub fn set_num_lines_visible(&self, num: usize) -> &Self {
+        set_str_attribute(self.handle(), "NUMLIN_VISIBLE\0", &format!("{}\0", num));
+        self
+    }
+
+    /// Sets the number of columns that are visible when the matrix is first shown, which
+    /// determines its natural width.
+    pub fn set_num_columns_visible(&self, num: usize) -> &Self {
+        set_str_attribute(self.handle(), "NUMCOL_VISIBLE\0", &format!("{}\0", num));
+        self
+    }
+
+    /// Inserts `count` empty lines so that the first new line is at `index`.
+    pub fn insert_lines(&self, index: usize, count: usize) -> &Self {
+        // ADDLIN adds lines after the specified line, and 0 is the title line.
+        set_str_attribute(self.handle(), "ADDLIN\0", &format!("{}-{}\0", index, count));
+        self
+    }
+
+    /// Removes `count` lines starting at `index`.
+    pub fn remove_lines(&self, index: usize, count: usize) -> &Self {
+        set_str_attribute(self.handle(), "DELLIN\0", &format!("{}-{}\0", index + 1, count));
+        self
+    }
+
+    /// Inserts `count` empty columns so that the first new column is at `index`.
+    pub fn insert_columns(&self, index: usize, count: usize) -> &Self {
+        set_str_attribute(self.handle(), "ADDCOL\0", &format!("{}-{}\0", index, count));
+        self
+    }
+
+    /// Removes `count` columns starting at `index`.
+    pub fn remove_columns(&self, index: usize, count: usize) -> &Self {
+        set_str_attribute(self.handle(), "DELCOL\0", &format!("{}-{}\0", index + 1, count));
+        self
+    }
+
+    pub fn cell(&self, line: usize, column: usize) -> String {
+        get_str_attribute_id2(self.handle(), "\0", line as i32 + 1, column as i32 + 1)
+    }
+
+    /// Sets the text of a cell. Call `redraw_cells` afterward if the matrix is visible.
+    pub fn set_cell(&self, line: usize, column: usize, text: &str) -> &Self {
+        set_str_attribute_id2(self.handle(), "\0", line as i32 + 1, column as i32 + 1, text);
+        self
+    }
+
+    pub fn line_title(&self, line: usize) -> String {
+        get_str_attribute_id2(self.handle(), "\0", line as i32 + 1, 0)
+    }
+
+    pub fn set_line_title(&self, line: usize, title: &str) -> &Self {
+        set_str_attribute_id2(self.handle(), "\0", line as i32 + 1, 0, title);
+        self
+    }
+
+    pub fn column_title(&self, column: usize) -> String {
+        get_str_attribute_id2(self.handle(), "\0", 0, column as i32 + 1)
+    }
+
+    pub fn set_column_title(&self, column: usize, title: &str) -> &Self {
+        set_str_attribute_id2(self.handle(), "\0", 0, column as i32 + 1, title);
+        self
+    }
+
+    /// Returns the width of a column in pixels.
+    pub fn column_width(&self, column: usize) -> u32 {
+        unsafe {
+            let attr = format!("RASTERWIDTH{}\0", column + 1);
+            let s = get_str_attribute_slice(self.handle(), &attr);
+            s.parse().unwrap_or(0)
+        }
+    }
+
+    /// Sets the width of a column in pixels.
+    pub fn set_column_width(&self, column: usize, width: u32) -> &Self {
+        set_str_attribute(self.handle(), &format!("RASTERWIDTH{}\0", column + 1), &format!("{}\0", width));
+        self
+    }
+
+    /// Sets the width of the title column in pixels. A width of 0 hides the title column.
+    pub fn set_title_column_width(&self, width: u32) -> &Self {
+        set_str_attribute(self.handle(), "RASTERWIDTH0\0", &format!("{}\0", width));
+        self
+    }
+
+    /// Returns the height of a line in pixels.
+    pub fn line_height(&self, line: usize) -> u32 {
+        unsafe {
+            let attr = format!("RASTERHEIGHT{}\0", line + 1);
+            let s = get_str_attribute_slice(self.handle(), &attr);
+            s.parse().unwrap_or(0)
+        }
+    }
+
+    /// Sets the height of a line in pixels.
+    pub fn set_line_height(&self, line: usize, height: u32) -> &Self {
+        set_str_attribute(self.handle(), &format!("RASTERHEIGHT{}\0", line + 1), &format!("{}\0", height));
+        self
+    }
+
+    /// Sets the height of the title line in pixels. A height of 0 hides the title line.
+    pub fn set_title_line_height(&self, height: u32) -> &Self {
+        set_str_attribute(self.handle(), "RASTERHEIGHT0\0", &format!("{}\0", height));
+        self
+    }
+
+    /// Sets the background color of a cell, or resets it to the default if `color` is `None`.
+    pub fn set_cell_bg_color(&self, line: usize, column: usize, color: Option<Color>) -> &Self {
+        let (lin, col) = (line as i32 + 1, column as i32 + 1);
+        match color {
+            Some(color) => set_str_attribute_id2(self.handle(), "BGCOLOR\0", lin, col, &color.to_str()),
+            None => reset_attribute_id2(self.handle(), "BGCOLOR\0", lin, col),
+        }
+        self
+    }
+
+    /// Sets the text color of a cell, or resets it to the default if `color` is `None`.
+    pub fn set_cell_fg_color(&self, line: usize, column: usize, color: Option<Color>) -> &Self {
+        let (lin, col) = (line as i32 + 1, column as i32 + 1);
+        match color {
+            Some(color) => set_str_attribute_id2(self.handle(), "FGCOLOR\0", lin, col, &color.to_str()),
+            None => reset_attribute_id2(self.handle(), "FGCOLOR\0", lin, col),
+        }
+        self
+    }
+
+    /// Returns the horizontal alignment of a column, or `None` if it hasn't been set and the
+    /// matrix's default is used.
+    pub fn column_alignment(&self, column: usize) -> Option<HAlignment> {
+        unsafe {
+            let attr = format!("ALIGNMENT{}\0", column + 1);
+            let s = get_str_attribute_slice(self.handle(), &attr);
+            // The value can also have a vertical alignment after a colon, like "ALEFT:ACENTER".
+            match s.split(':').next().unwrap() {
+                "ALEFT" => Some(HAlignment::Left),
+                "ACENTER" => Some(HAlignment::Center),
+                "ARIGHT" => Some(HAlignment::Right),
+                _ => None,
+            }
+        }
+    }
+
+    pub fn set_column_alignment(&self, column: usize, alignment: HAlignment) -> &Self {
+        set_str_attribute(self.handle(), &format!("ALIGNMENT{}\0", column + 1), alignment.to_str());
+        self
+    }
+
+    /// Sets the alignment of a single cell, overriding its column's alignment.
+    pub fn set_cell_alignment(&self, line: usize, column: usize, alignment: HAlignment) -> &Self {
+        set_str_attribute_id2(self.handle(), "ALIGNMENT\0", line as i32 + 1, column as i32 + 1,
+                              alignment.to_str());
+        self
+    }
+
+    /// If true, no cells can be edited. The default is false.
+    pub fn read_only(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "READONLY\0") == "YES"
+        }
+    }
+
+    pub fn set_read_only(&self, read_only: bool) -> &Self {
+        set_str_attribute(self.handle(), "READONLY\0", if read_only { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Returns the focused cell as (line, column).
+    pub fn focus_cell(&self) -> (usize, usize) {
+        let (lin, col) = get_int_int_attribute(self.handle(), "FOCUSCELL\0");
+        ((lin - 1).max(0) as usize, (col - 1).max(0) as usize)
+    }
+
+    pub fn set_focus_cell(&self, line: usize, column: usize) -> &Self {
+        set_str_attribute(self.handle(), "FOCUSCELL\0", &format!("{}:{}\0", line + 1, column + 1));
+        self
+    }
+
+    /// Redraws all cells. Changing the text or color of a cell doesn't redraw it, so call this
+    /// after making changes to a visible matrix.
+    pub fn redraw_cells(&self) -> &Self {
+        set_str_attribute(self.handle(), "REDRAW\0", "ALL\0");
+        self
+    }
+}
+
+impl_control_traits!(Matrix);
+
+impl ActiveAttribute for Matrix {}
+impl CanFocusAttribute for Matrix {}
+impl ExpandAttribute for Matrix {}
+impl MinMaxSizeAttribute for Matrix {}
+impl TipAttribute for Matrix {}
+impl VisibleAttribute for Matrix {}
+
+impl MenuCommonCallbacks for Matrix {}
+impl GetKillFocusCallbacks for Matrix {}
+impl EnterLeaveWindowCallbacks for Matrix {}
+
+/// The cell whose value is being requested in virtual mode. A `line` or `column` of `None` means
+/// the title line or column.
+#[derive(Clone)]
+pub struct MatrixValueArgs {
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    _dummy: (),
+}
+
+#[derive(Clone)]
+pub struct MatrixValueEditArgs<'a> {
+    pub line: usize,
+    pub column: usize,
+    pub new_value: &'a str,
+    _dummy: (),
+}
+
+#[derive(Clone)]
+pub struct MatrixEditionArgs {
+    pub line: usize,
+    pub column: usize,
+    /// True if the cell is entering edit mode and false if it is leaving it.
+    pub entering: bool,
+    /// When leaving edit mode, true if the edit was accepted and the new value will be stored.
+    pub update: bool,
+    _dummy: (),
+}
+
+// The string returned from VALUE_CB has to stay valid after the callback returns. IUP copies it
+// before calling VALUE_CB again.
+thread_local!(
+    static VALUE_BUFFER: RefCell<Vec<u8>> = RefCell::new(Vec::new())
+);
+
+fn title_index(i: c_int) -> Option<usize> {
+    if i == 0 { None } else { Some(i as usize - 1) }
+}
+
+impl_callbacks! {
+    Matrix {
+        // Adding a callback to this event puts the matrix in virtual mode, where cell values
+        // aren't stored in the matrix. Instead, the callback is called whenever a cell needs
+        // drawn, so the number of lines can be huge. If multiple callbacks return `Some`, the
+        // value from the last one is used.
+        "VALUE_CB\0" => value_event {
+            VALUE_CALLBACKS<FnMut(&MatrixValueArgs) -> Option<String>, MatrixValueCallbackToken>
+        }
+        unsafe extern fn value_cb(ih: *mut Ihandle, lin: c_int, col: c_int) -> *mut c_char {
+            let mut found = false;
+            with_callbacks(ih, &VALUE_CALLBACKS, |cbs| {
+                let args = MatrixValueArgs {
+                    line: title_index(lin),
+                    column: title_index(col),
+                    _dummy: (),
+                };
+                let mut value = None;
+                for cb in cbs {
+                    if let Some(v) = (&mut *cb.1.borrow_mut())(&args) {
+                        value = Some(v);
+                    }
+                }
+                if let Some(value) = value {
+                    VALUE_BUFFER.with(|buf| {
+                        let mut buf = buf.borrow_mut();
+                        buf.clear();
+                        buf.extend(value.bytes().map(|c| if c == b'\0' { b'?' } else { c }));
+                        buf.push(0);
+                    });
+                    found = true;
+                }
+                IUP_DEFAULT
+            });
+            if found {
+                VALUE_BUFFER.with(|buf| buf.borrow().as_ptr() as *mut c_char)
+            } else {
+                ptr::null_mut()
+            }
+        }
+
+        // Called after the user edits a cell. In virtual mode, this is where the new value should
+        // be stored.
+        "VALUE_EDIT_CB\0" => value_edit_event {
+            VALUE_EDIT_CALLBACKS<FnMut(&MatrixValueEditArgs), MatrixValueEditCallbackToken>
+        }
+        unsafe extern fn value_edit_cb(ih: *mut Ihandle, lin: c_int, col: c_int, new_value: *mut c_char) -> c_int {
+            with_callbacks(ih, &VALUE_EDIT_CALLBACKS, |cbs| {
+                let new_value = CStr::from_ptr(new_value).to_string_lossy();
+                let args = MatrixValueEditArgs {
+                    line: lin as usize - 1,
+                    column: col as usize - 1,
+                    new_value: &*new_value,
+                    _dummy: (),
+                };
+                for cb in cbs {
+                    (&mut *cb.1.borrow_mut())(&args);
+                }
+                IUP_DEFAULT
+            })
+        }
+
+        // Return `CallbackAction::Ignore` when entering edit mode to prevent the cell from being
+        // edited or when leaving edit mode to keep editing (such as when the new value is invalid).
+        "EDITION_CB\0" => edition_event {
+            EDITION_CALLBACKS<FnMut(&MatrixEditionArgs) -> CallbackAction, MatrixEditionCallbackToken>
+        }
+        unsafe extern fn edition_cb(ih: *mut Ihandle, lin: c_int, col: c_int, mode: c_int, update: c_int) -> c_int {
+            with_callbacks(ih, &EDITION_CALLBACKS, |cbs| {
+                let args = MatrixEditionArgs {
+                    line: lin as usize - 1,
+                    column: col as usize - 1,
+                    entering: mode == 1,
+                    update: update == 1,
+                    _dummy: (),
+                };
+                let mut action = CallbackAction::Default;
+                for cb in cbs {
+                    match (&mut *cb.1.borrow_mut())(&args) {
+                        CallbackAction::Default => {},
+                        cb_action => action = cb_action,
+                    }
+                }
+                action.to_int()
+            })
+        }
+    }
+}
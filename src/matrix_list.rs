@@ -0,0 +1,445 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+use super::control_prelude::*;
+use super::{Color, Image, KeyboardMouseStatus};
+use super::controls_sys::{controls_open, IupMatrixList};
+use super::extra_refs::ExtraRefKey;
+use super::image::set_image_attribute;
+
+const EXTRA_REF_IMAGE_CHECK: ExtraRefKey = ExtraRefKey(3);
+const EXTRA_REF_IMAGE_UNCHECK: ExtraRefKey = ExtraRefKey(4);
+
+/// A list where each item can have a checkbox and a color marker. Requires the `iup-controls`
+/// feature.
+///
+/// Item indexes are zero-based.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use clear_coat::*;
+/// let list = MatrixList::new();
+/// list.set_show_checkboxes(true);
+/// list.append_item("Milk");
+/// list.append_item("Eggs");
+/// list.set_checked(1, true);
+/// ```
+#[derive(Clone)]
+pub struct MatrixList(HandleRc);
+
+impl MatrixList {
+    pub fn new() -> Self {
+        unsafe {
+            controls_open();
+            let ih = IupMatrixList();
+            MatrixList(HandleRc::new(ih))
+        }
+    }
+
+    pub fn count(&self) -> usize {
+        unsafe {
+            let s = get_str_attribute_slice(self.handle(), "COUNT\0");
+            s.parse().expect("could not convert COUNT to an integer")
+        }
+    }
+
+    /// Sets the number of items. New items are empty, and items past the new count are removed.
+    pub fn set_count(&self, count: usize) -> &Self {
+        set_str_attribute(self.handle(), "COUNT\0", &format!("{}\0", count));
+        self
+    }
+
+    // An `index` of 0 is the first item.
+    pub fn item(&self, index: usize) -> String {
+        get_str_attribute(self.handle(), &format!("{}\0", index + 1))
+    }
+
+    // An `index` of 0 is the first item.
+    pub fn set_item(&self, index: usize, text: &str) -> &Self {
+        set_str_attribute(self.handle(), &format!("{}\0", index + 1), text);
+        self
+    }
+
+    pub fn set_items<I, T>(&self, items: I) -> &Self
+                           where I: IntoIterator<Item=T>, T: AsRef<str> {
+        let items: Vec<T> = items.into_iter().collect();
+        self.set_count(items.len());
+        for (index, item) in items.iter().enumerate() {
+            self.set_item(index, item.as_ref());
+        }
+        self
+    }
+
+    pub fn append_item(&self, text: &str) -> &Self {
+        let count = self.count();
+        self.insert_item(count, text)
+    }
+
+    /// Inserts an item so that it is at `index`. An `index` equal to `count()` appends the item.
+    pub fn insert_item(&self, index: usize, text: &str) -> &Self {
+        // ADDLIN adds a line after the specified line, and 0 is the title line.
+        set_str_attribute(self.handle(), "ADDLIN\0", &format!("{}\0", index));
+        self.set_item(index, text)
+    }
+
+    // An `index` of 0 is the first item.
+    pub fn remove_item(&self, index: usize) -> &Self {
+        set_str_attribute(self.handle(), "DELLIN\0", &format!("{}\0", index + 1));
+        self
+    }
+
+    pub fn clear(&self) -> &Self {
+        self.set_count(0)
+    }
+
+    /// Sets the number of items that are visible when the list is first shown, which determines
+    /// its natural height.
+    pub fn set_visible_lines(&self, lines: usize) -> &Self {
+        set_str_attribute(self.handle(), "VISIBLELINES\0", &format!("{}\0", lines));
+        self
+    }
+
+    /// Shows a checkbox column. The default is false.
+    pub fn set_show_checkboxes(&self, show: bool) -> &Self {
+        self.set_column_order(show, self.shows_colors())
+    }
+
+    /// Shows a column with a color marker for each item. The default is false.
+    pub fn set_show_colors(&self, show: bool) -> &Self {
+        self.set_column_order(self.shows_checkboxes(), show)
+    }
+
+    pub fn shows_checkboxes(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "COLUMNORDER\0").contains("IMAGE")
+        }
+    }
+
+    pub fn shows_colors(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "COLUMNORDER\0").contains("COLOR")
+        }
+    }
+
+    fn set_column_order(&self, image: bool, color: bool) -> &Self {
+        let order = match (image, color) {
+            (false, false) => "LABEL\0",
+            (true, false) => "IMAGE:LABEL\0",
+            (false, true) => "COLOR:LABEL\0",
+            (true, true) => "IMAGE:COLOR:LABEL\0",
+        };
+        set_str_attribute(self.handle(), "COLUMNORDER\0", order);
+        self
+    }
+
+    /// If true, the user can edit the text of items, and an empty line is shown at the end of the
+    /// list for adding items. The default is false.
+    pub fn editable(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "EDITABLE\0") == "YES"
+        }
+    }
+
+    pub fn set_editable(&self, editable: bool) -> &Self {
+        set_str_attribute(self.handle(), "EDITABLE\0", if editable { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// If true, a delete button is shown beside the focused item, which removes the item when
+    /// clicked. The default is false.
+    pub fn set_show_delete(&self, show: bool) -> &Self {
+        set_str_attribute(self.handle(), "SHOWDELETE\0", if show { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Returns whether the checkbox of an item is checked.
+    pub fn checked(&self, index: usize) -> bool {
+        unsafe {
+            let attr = format!("IMAGEVALUE{}\0", index + 1);
+            get_str_attribute_slice(self.handle(), &attr) == "ON"
+        }
+    }
+
+    pub fn set_checked(&self, index: usize, checked: bool) -> &Self {
+        set_str_attribute(self.handle(), &format!("IMAGEVALUE{}\0", index + 1),
+                          if checked { "ON\0" } else { "OFF\0" });
+        self
+    }
+
+    /// Returns the indexes of all checked items.
+    pub fn checked_items(&self) -> Vec<usize> {
+        (0..self.count()).filter(|&i| self.checked(i)).collect()
+    }
+
+    /// If false, the user can't change the checkbox of an item. The default is true.
+    pub fn set_checkbox_active(&self, index: usize, active: bool) -> &Self {
+        set_str_attribute(self.handle(), &format!("IMAGEACTIVE{}\0", index + 1),
+                          if active { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Sets the images drawn for checked and unchecked checkboxes, replacing the default check
+    /// marks. The list keeps a reference to the images.
+    pub fn set_checkbox_images(&self, checked: Option<&Image>, unchecked: Option<&Image>) -> &Self {
+        set_image_attribute(self.handle(), "IMAGECHECK\0", EXTRA_REF_IMAGE_CHECK, checked);
+        set_image_attribute(self.handle(), "IMAGEUNCHECK\0", EXTRA_REF_IMAGE_UNCHECK, unchecked);
+        self
+    }
+
+    /// Sets the color of an item's color marker. The color column has to be shown with
+    /// `set_show_colors`.
+    pub fn set_item_color(&self, index: usize, color: Color) -> &Self {
+        set_str_attribute(self.handle(), &format!("COLOR{}\0", index + 1), &color.to_str());
+        self
+    }
+
+    /// Sets the text color of an item, or resets it to the default if `color` is `None`.
+    pub fn set_item_fg_color(&self, index: usize, color: Option<Color>) -> &Self {
+        let attr = format!("ITEMFGCOLOR{}\0", index + 1);
+        match color {
+            Some(color) => set_str_attribute(self.handle(), &attr, &color.to_str()),
+            None => reset_attribute(self.handle(), &attr),
+        }
+        self
+    }
+
+    /// Sets the background color of an item, or resets it to the default if `color` is `None`.
+    pub fn set_item_bg_color(&self, index: usize, color: Option<Color>) -> &Self {
+        let attr = format!("ITEMBGCOLOR{}\0", index + 1);
+        match color {
+            Some(color) => set_str_attribute(self.handle(), &attr, &color.to_str()),
+            None => reset_attribute(self.handle(), &attr),
+        }
+        self
+    }
+
+    /// If false, the item is drawn grayed out and can't be selected or edited. The default is
+    /// true.
+    pub fn set_item_active(&self, index: usize, active: bool) -> &Self {
+        set_str_attribute(self.handle(), &format!("ITEMACTIVE{}\0", index + 1),
+                          if active { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Returns the index of the focused item or `None` if the list is empty.
+    pub fn value(&self) -> Option<usize> {
+        unsafe {
+            let s = get_str_attribute_slice(self.handle(), "VALUE\0");
+            s.parse::<usize>().ok().and_then(|i| i.checked_sub(1))
+        }
+    }
+
+    pub fn set_value(&self, index: usize) -> &Self {
+        set_str_attribute(self.handle(), "VALUE\0", &format!("{}\0", index + 1));
+        self
+    }
+
+    /// Redraws all items. Changing the text or color of an item doesn't redraw it, so call this
+    /// after making changes to a visible list.
+    pub fn redraw_items(&self) -> &Self {
+        set_str_attribute(self.handle(), "REDRAW\0", "ALL\0");
+        self
+    }
+}
+
+impl_control_traits!(MatrixList);
+
+impl ActiveAttribute for MatrixList {}
+impl CanFocusAttribute for MatrixList {}
+impl ExpandAttribute for MatrixList {}
+impl MinMaxSizeAttribute for MatrixList {}
+impl TipAttribute for MatrixList {}
+impl VisibleAttribute for MatrixList {}
+
+impl MenuCommonCallbacks for MatrixList {}
+impl GetKillFocusCallbacks for MatrixList {}
+impl EnterLeaveWindowCallbacks for MatrixList {}
+
+#[derive(Clone)]
+pub struct MatrixListActionArgs {
+    pub item_index: usize,
+    pub selected: bool,
+    _dummy: (),
+}
+
+#[derive(Clone)]
+pub struct MatrixListClickArgs {
+    pub item_index: usize,
+    pub status: KeyboardMouseStatus,
+    _dummy: (),
+}
+
+#[derive(Clone)]
+pub struct MatrixListEditionArgs {
+    pub item_index: usize,
+    /// True if the item is entering edit mode and false if it is leaving it.
+    pub entering: bool,
+    /// When leaving edit mode, true if the edit was accepted and the new text will be stored.
+    pub update: bool,
+    _dummy: (),
+}
+
+#[derive(Clone)]
+pub struct MatrixListCheckArgs {
+    pub item_index: usize,
+    pub checked: bool,
+    _dummy: (),
+}
+
+// IUP numbers items from 1. Line 0 is the title line, which a list doesn't show, so it shouldn't
+// be passed to callbacks, but it is ignored instead of wrapping around if it is.
+fn line_to_index(line: c_int) -> Option<usize> {
+    if line >= 1 { Some((line - 1) as usize) } else { None }
+}
+
+impl_callbacks! {
+    MatrixList {
+        // Called when an item is selected or deselected.
+        "LISTACTION_CB\0" => action_event {
+            LIST_ACTION_CALLBACKS<FnMut(&MatrixListActionArgs), MatrixListActionCallbackToken>
+        }
+        unsafe extern fn list_action_cb(ih: *mut Ihandle, item: c_int, state: c_int) -> c_int {
+            with_callbacks(ih, &LIST_ACTION_CALLBACKS, |cbs| {
+                let item_index = match line_to_index(item) {
+                    Some(index) => index,
+                    None => return IUP_DEFAULT,
+                };
+                let args = MatrixListActionArgs {
+                    item_index: item_index,
+                    selected: state == 1,
+                    _dummy: (),
+                };
+                for cb in cbs {
+                    (&mut *cb.1.borrow_mut())(&args);
+                }
+                IUP_DEFAULT
+            })
+        }
+
+        // Called when an item is clicked. Clicks on the empty line at the end of an editable
+        // list don't call this.
+        "LISTCLICK_CB\0" => click_event {
+            LIST_CLICK_CALLBACKS<FnMut(&MatrixListClickArgs) -> CallbackAction, MatrixListClickCallbackToken>
+        }
+        unsafe extern fn list_click_cb(ih: *mut Ihandle, lin: c_int, _col: c_int, status: *mut c_char) -> c_int {
+            with_callbacks(ih, &LIST_CLICK_CALLBACKS, |cbs| {
+                let item_index = match line_to_index(lin) {
+                    Some(index) => index,
+                    None => return IUP_DEFAULT,
+                };
+                let args = MatrixListClickArgs {
+                    item_index: item_index,
+                    status: KeyboardMouseStatus::from_cstr(status),
+                    _dummy: (),
+                };
+                let mut action = CallbackAction::Default;
+                for cb in cbs {
+                    match (&mut *cb.1.borrow_mut())(&args) {
+                        CallbackAction::Default => {},
+                        cb_action => action = cb_action,
+                    }
+                }
+                action.to_int()
+            })
+        }
+
+        // Return `CallbackAction::Ignore` when entering edit mode to prevent the item from being
+        // edited or when leaving edit mode to keep editing.
+        "LISTEDITION_CB\0" => edition_event {
+            LIST_EDITION_CALLBACKS<FnMut(&MatrixListEditionArgs) -> CallbackAction, MatrixListEditionCallbackToken>
+        }
+        unsafe extern fn list_edition_cb(ih: *mut Ihandle, lin: c_int, _col: c_int, mode: c_int, update: c_int) -> c_int {
+            with_callbacks(ih, &LIST_EDITION_CALLBACKS, |cbs| {
+                let item_index = match line_to_index(lin) {
+                    Some(index) => index,
+                    None => return IUP_DEFAULT,
+                };
+                let args = MatrixListEditionArgs {
+                    item_index: item_index,
+                    entering: mode == 1,
+                    update: update == 1,
+                    _dummy: (),
+                };
+                let mut action = CallbackAction::Default;
+                for cb in cbs {
+                    match (&mut *cb.1.borrow_mut())(&args) {
+                        CallbackAction::Default => {},
+                        cb_action => action = cb_action,
+                    }
+                }
+                action.to_int()
+            })
+        }
+
+        // Called after the user checks or unchecks an item's checkbox.
+        "IMAGEVALUECHANGED_CB\0" => check_event {
+            CHECK_CALLBACKS<FnMut(&MatrixListCheckArgs), MatrixListCheckCallbackToken>
+        }
+        unsafe extern fn image_value_changed_cb(ih: *mut Ihandle, item: c_int, state: c_int) -> c_int {
+            with_callbacks(ih, &CHECK_CALLBACKS, |cbs| {
+                let item_index = match line_to_index(item) {
+                    Some(index) => index,
+                    None => return IUP_DEFAULT,
+                };
+                let args = MatrixListCheckArgs {
+                    item_index: item_index,
+                    checked: state == 1,
+                    _dummy: (),
+                };
+                for cb in cbs {
+                    (&mut *cb.1.borrow_mut())(&args);
+                }
+                IUP_DEFAULT
+            })
+        }
+
+        // Called when the user adds an item by editing the empty line at the end of an editable
+        // list. Return `CallbackAction::Ignore` to prevent the item from being added.
+        "LISTINSERT_CB\0" => insert_event {
+            LIST_INSERT_CALLBACKS<FnMut(usize) -> CallbackAction, MatrixListInsertCallbackToken>
+        }
+        unsafe extern fn list_insert_cb(ih: *mut Ihandle, lin: c_int) -> c_int {
+            with_callbacks(ih, &LIST_INSERT_CALLBACKS, |cbs| {
+                let item_index = match line_to_index(lin) {
+                    Some(index) => index,
+                    None => return IUP_DEFAULT,
+                };
+                let mut action = CallbackAction::Default;
+                for cb in cbs {
+                    match (&mut *cb.1.borrow_mut())(item_index) {
+                        CallbackAction::Default => {},
+                        cb_action => action = cb_action,
+                    }
+                }
+                action.to_int()
+            })
+        }
+
+        // Called when the user removes an item with the delete button. Return
+        // `CallbackAction::Ignore` to prevent the item from being removed.
+        "LISTREMOVE_CB\0" => remove_event {
+            LIST_REMOVE_CALLBACKS<FnMut(usize) -> CallbackAction, MatrixListRemoveCallbackToken>
+        }
+        unsafe extern fn list_remove_cb(ih: *mut Ihandle, lin: c_int) -> c_int {
+            with_callbacks(ih, &LIST_REMOVE_CALLBACKS, |cbs| {
+                let item_index = match line_to_index(lin) {
+                    Some(index) => index,
+                    None => return IUP_DEFAULT,
+                };
+                let mut action = CallbackAction::Default;
+                for cb in cbs {
+                    match (&mut *cb.1.borrow_mut())(item_index) {
+                        CallbackAction::Default => {},
+                        cb_action => action = cb_action,
+                    }
+                }
+                action.to_int()
+            })
+        }
+    }
+}
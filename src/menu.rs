@@ -6,10 +6,39 @@
  */
 
 use super::control_prelude::*;
+use std::ffi::CStr;
+use super::{Image, MouseButton, Popup, ScreenPosition};
 use super::containers::{
     Container,
     wrapper_to_handle_vec,
 };
+use super::callbacks::{ButtonArgs, ButtonCallbackToken};
+use super::extra_refs::{add_extra_ref, ExtraRefKey};
+use super::image::{ImageAttribute, set_image_attribute};
+
+const EXTRA_REF_IMAGE_PRESSED: ExtraRefKey = ExtraRefKey(11);
+const EXTRA_REF_CONTEXT_MENU: ExtraRefKey = ExtraRefKey(12);
+
+fn is_item(ih: *mut Ihandle) -> bool {
+    unsafe { CStr::from_ptr(IupGetClassName(ih)).to_bytes() == b"item" }
+}
+
+// Adds the VALUE of every item in the subtree of `ih` that has one to `values`.
+fn item_values(ih: *mut Ihandle, values: &mut Vec<(*mut Ihandle, String)>) {
+    unsafe {
+        if is_item(ih) {
+            let value = get_str_attribute(ih, "VALUE\0");
+            if !value.is_empty() {
+                values.push((ih, value));
+            }
+        }
+        let mut child = IupGetNextChild(ih, ptr::null_mut());
+        while !child.is_null() {
+            item_values(child, values);
+            child = IupGetNextChild(ih, child);
+        }
+    }
+}
 
 /// Implemented by controls that can be added as children of a `Menu`.
 pub trait MenuSubitem : Control {
@@ -41,14 +70,203 @@ impl Menu {
         let handle = IupMenuv(children);
         Menu(HandleRc::new(handle))
     }
+
+    pub(crate) fn from_handle_rc(rc: HandleRc) -> Self {
+        Menu(rc)
+    }
+
+    pub fn radio(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "RADIO\0") == "YES"
+        }
+    }
+
+    /// Sets whether the items in the menu form a radio group, where choosing an item checks it
+    /// and unchecks the other items. It can only be changed before the menu is mapped.
+    pub fn set_radio(&self, radio: bool) -> &Self {
+        set_str_attribute(self.handle(), "RADIO\0", if radio { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Adds an item, separator, or submenu at the end of the menu.
+    pub fn append_item(&self, item: &MenuSubitem) -> &Self {
+        self.insert_item(self.child_count(), item)
+    }
+
+    /// Inserts an item, separator, or submenu so that it has the index `pos`. Unlike
+    /// `Container::insert`, this can be used while the menu is shown, like in a dialog's menu
+    /// bar, because the new item is mapped if the menu is.
+    pub fn insert_item(&self, pos: usize, item: &MenuSubitem) -> &Self {
+        let count = self.child_count();
+        assert!(pos <= count, "menu item index out of bounds");
+        unsafe {
+            // IupInsert with a null reference child inserts at the front, so appending has to use
+            // IupAppend.
+            let parent = if pos == count {
+                IupAppend(self.handle(), item.handle())
+            } else {
+                IupInsert(self.handle(), self.child_handle(pos), item.handle())
+            };
+            if parent.is_null() {
+                panic!("failed to insert menu item");
+            }
+            if self.is_mapped() {
+                IupMap(item.handle());
+            }
+        }
+        self
+    }
+
+    /// Removes the item at index `pos`. It is destroyed unless there is still a wrapper
+    /// referencing it, in which case it can be added to a menu again. Removing a submenu destroys
+    /// the menu inside it the same way.
+    pub fn remove_item(&self, pos: usize) -> &Self {
+        let child = self.child_handle(pos);
+        unsafe {
+            // Taking a reference before detaching means dropping it destroys the item if nothing
+            // else references it, the same as if a wrapper had been dropped. Detaching also
+            // unmaps the item.
+            let child = HandleRc::new(child);
+            IupDetach(child.get());
+        }
+        self
+    }
+
+    /// Moves the item at index `from` so that it has the index `to`.
+    pub fn move_item(&self, from: usize, to: usize) -> &Self {
+        let count = self.child_count();
+        assert!(from < count && to < count, "menu item index out of bounds");
+        if from == to {
+            return self;
+        }
+        let child = self.child_handle(from);
+        // The item is inserted before the item currently at `to`, or after it when moving down.
+        let ref_child = if to < from {
+            self.child_handle(to)
+        } else if to + 1 < count {
+            self.child_handle(to + 1)
+        } else {
+            ptr::null_mut()
+        };
+        let mapped = self.is_mapped();
+        unsafe {
+            // Native menu items can't be moved, so a mapped item is unmapped and mapped again in
+            // its new position. The check state isn't kept by IUP when an item is unmapped, and
+            // unmapping a submenu unmaps all the items inside it.
+            let mut values = vec![];
+            if mapped {
+                item_values(child, &mut values);
+                IupUnmap(child);
+            }
+            // Reparenting doesn't destroy the item or change its references.
+            if IupReparent(child, self.handle(), ref_child) != IUP_NOERROR {
+                panic!("failed to move menu item");
+            }
+            if mapped {
+                IupMap(child);
+                for (item, value) in values {
+                    set_str_attribute(item, "VALUE\0", &value);
+                }
+            }
+        }
+        self
+    }
+
+    fn child_handle(&self, pos: usize) -> *mut Ihandle {
+        let child = unsafe { IupGetChild(self.handle(), pos as c_int) };
+        assert!(!child.is_null(), "menu item index out of bounds");
+        child
+    }
+
+    fn is_mapped(&self) -> bool {
+        !get_attribute_ptr(self.handle(), "WID\0").is_null()
+    }
+
+    /// Shows the menu with its top left corner at (`x`, `y`) relative to the top left corner of
+    /// the control, and returns after the user picks an item or closes the menu.
+    ///
+    /// Returns `Err(())` if the control isn't mapped, since its position isn't known until it is.
+    pub fn popup_at(&self, control: &Control, x: i32, y: i32) -> Result<(), ()> {
+        if get_attribute_ptr(control.handle(), "WID\0").is_null() {
+            return Err(());
+        }
+        let pos = unsafe { get_str_attribute_slice(control.handle(), "SCREENPOSITION\0") };
+        let mut parts = pos.split(',').map(|part| part.trim().parse::<i32>());
+        let (control_x, control_y) = match (parts.next(), parts.next()) {
+            (Some(Ok(control_x)), Some(Ok(control_y))) => (control_x, control_y),
+            _ => return Err(()),
+        };
+        self.popup(ScreenPosition::Absolute(control_x + x), ScreenPosition::Absolute(control_y + y))
+    }
+
+    /// Called just before the menu is shown, including when it is opened as a submenu. It can
+    /// be used to update the items, like making them active or inactive.
+    pub fn open_event<'a>(&'a self) -> Event<'a, FnMut(), MenuOpenCallbackToken>
+    where &'a Self: CoerceUnsized<&'a Control> {
+        Event::new(self as &'a Control, &MENU_OPEN_CALLBACKS)
+    }
+
+    /// Called just after the menu is closed.
+    pub fn menu_close_event<'a>(&'a self) -> Event<'a, FnMut(), MenuCloseCallbackToken>
+    where &'a Self: CoerceUnsized<&'a Control> {
+        Event::new(self as &'a Control, &MENU_CLOSE_CALLBACKS)
+    }
 }
 
 impl_control_traits!(Menu);
 
 impl Container for Menu {}
+// IUP shows a menu at the position and returns after the user picks an item or closes it.
+impl Popup for Menu {}
 
 impl MenuCommonCallbacks for Menu {}
 
+callback_token!(MenuOpenCallbackToken);
+thread_local!(
+    static MENU_OPEN_CALLBACKS: CallbackRegistry<FnMut(), MenuOpenCallbackToken> =
+        CallbackRegistry::new("OPEN_CB\0", menu_open_cb)
+);
+extern fn menu_open_cb(ih: *mut Ihandle) -> c_int {
+    simple_callback(ih, &MENU_OPEN_CALLBACKS)
+}
+
+callback_token!(MenuCloseCallbackToken);
+thread_local!(
+    static MENU_CLOSE_CALLBACKS: CallbackRegistry<FnMut(), MenuCloseCallbackToken> =
+        CallbackRegistry::new("MENUCLOSE_CB\0", menu_close_cb)
+);
+extern fn menu_close_cb(ih: *mut Ihandle) -> c_int {
+    simple_callback(ih, &MENU_CLOSE_CALLBACKS)
+}
+
+/// Implemented by controls that can show a menu when they are right-clicked.
+pub trait ContextMenu : ButtonCallback {
+    /// Shows the menu at the mouse position when the control is clicked with the right mouse
+    /// button. The menu is shown when the button is released.
+    ///
+    /// The control keeps a reference to the menu until the control is destroyed, even if the
+    /// returned token is passed to `button_event().remove()`. The callback doesn't hold the
+    /// menu itself, so an item callback that captures the control doesn't keep the menu and
+    /// control alive after the control's dialog is destroyed.
+    fn context_menu<'a>(&'a self, menu: &Menu) -> ButtonCallbackToken
+    where &'a Self: CoerceUnsized<&'a Control> {
+        let menu_ih = menu.handle();
+        unsafe {
+            add_extra_ref(self.handle(), EXTRA_REF_CONTEXT_MENU, HandleRc::new(menu_ih));
+        }
+        self.button_event().add(move |args: &ButtonArgs| {
+            if let (MouseButton::Button3, false) = (args.button, args.pressed) {
+                // The extra reference keeps the menu alive as long as the control can be clicked.
+                let menu = Menu::from_handle_rc(unsafe { HandleRc::new(menu_ih) });
+                let _ = menu.popup(ScreenPosition::MousePos, ScreenPosition::MousePos);
+            }
+            CallbackAction::Default
+        })
+    }
+}
+
+impl<T: ButtonCallback> ContextMenu for T {}
+
 
 
 #[derive(Clone)]
@@ -77,6 +295,7 @@ impl Submenu {
 impl_control_traits!(Submenu);
 
 impl MenuSubitem for Submenu {}
+impl MenuCommonCallbacks for Submenu {}
 
 
 
@@ -102,17 +321,59 @@ impl Item {
         }
     }
 
+    pub fn checked(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "VALUE\0") == "ON"
+        }
+    }
+
+    /// Sets whether a check mark is shown next to the item.
+    pub fn set_checked(&self, checked: bool) -> &Self {
+        set_str_attribute(self.handle(), "VALUE\0", if checked { "ON\0" } else { "OFF\0" });
+        self
+    }
+
+    pub fn auto_toggle(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "AUTOTOGGLE\0") == "YES"
+        }
+    }
+
+    /// Sets whether the item is checked or unchecked automatically when it is chosen. The new
+    /// state can be read with `checked` in `action_event`. Items in a radio menu don't need this.
+    pub fn set_auto_toggle(&self, auto_toggle: bool) -> &Self {
+        set_str_attribute(self.handle(), "AUTOTOGGLE\0", if auto_toggle { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Sets the image shown next to the item when it is checked, or clears it if `image` is
+    /// `None`. The image set with `set_image` is shown when the item is unchecked. The item keeps
+    /// a reference to the image.
+    pub fn set_image_pressed(&self, image: Option<&Image>) -> &Self {
+        set_image_attribute(self.handle(), "IMPRESS\0", EXTRA_REF_IMAGE_PRESSED, image);
+        self
+    }
+
     pub fn action_event<'a>(&'a self) -> Event<'a, FnMut(), ItemActionCallbackToken>
     where &'a Self: CoerceUnsized<&'a Control> {
         Event::new(self as &'a Control, &ITEM_ACTION_CALLBACKS)
     }
+
+    /// Called when the item is highlighted by moving the mouse over it or selecting it with the
+    /// keyboard. It can be used to show a description of the item in a status bar.
+    pub fn highlight_event<'a>(&'a self) -> Event<'a, FnMut(), ItemHighlightCallbackToken>
+    where &'a Self: CoerceUnsized<&'a Control> {
+        Event::new(self as &'a Control, &ITEM_HIGHLIGHT_CALLBACKS)
+    }
 }
 
 impl_control_traits!(Item);
 
 impl MenuSubitem for Item {}
+impl MenuCommonCallbacks for Item {}
 
 impl ActiveAttribute for Item {}
+impl ImageAttribute for Item {}
 impl TitleAttribute for Item {}
 
 
@@ -125,6 +386,32 @@ extern fn item_action_cb(ih: *mut Ihandle) -> c_int {
     simple_callback(ih, &ITEM_ACTION_CALLBACKS)
 }
 
+// Does what choosing the item from its menu does. It is used by keyboard shortcuts.
+pub fn choose_item(item: &Item) {
+    if !item.active() {
+        return;
+    }
+    let in_radio_menu = unsafe {
+        let parent = IupGetParent(item.handle());
+        !parent.is_null() && get_str_attribute_slice(parent, "RADIO\0") == "YES"
+    };
+    if in_radio_menu {
+        item.set_checked(true);
+    } else if item.auto_toggle() {
+        item.set_checked(!item.checked());
+    }
+    item_action_cb(item.handle());
+}
+
+callback_token!(ItemHighlightCallbackToken);
+thread_local!(
+    static ITEM_HIGHLIGHT_CALLBACKS: CallbackRegistry<FnMut(), ItemHighlightCallbackToken> =
+        CallbackRegistry::new("HIGHLIGHT_CB\0", item_highlight_cb)
+);
+extern fn item_highlight_cb(ih: *mut Ihandle) -> c_int {
+    simple_callback(ih, &ITEM_HIGHLIGHT_CALLBACKS)
+}
+
 
 
 #[derive(Clone)]
@@ -143,3 +430,4 @@ impl Separator {
 impl_control_traits!(Separator);
 
 impl MenuSubitem for Separator {}
+impl MenuCommonCallbacks for Separator {}
@@ -0,0 +1,295 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+// A small software renderer for drawing into an RGBA buffer. It is used for custom-drawn
+// controls like `LineGraph` so that they draw the same on every platform and can be rendered
+// without a window.
+
+use super::{Color, Rect};
+
+pub const GLYPH_WIDTH: i32 = 5;
+pub const GLYPH_HEIGHT: i32 = 7;
+// The horizontal distance from the start of one character to the start of the next.
+pub const GLYPH_ADVANCE: i32 = GLYPH_WIDTH + 1;
+
+pub struct Raster {
+    width: u32,
+    height: u32,
+    // RGBA, left to right, top to bottom
+    pixels: Vec<u8>,
+    // x, y, right, bottom
+    clip: (i32, i32, i32, i32),
+}
+
+impl Raster {
+    pub fn new(width: u32, height: u32, background: Color) -> Raster {
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 4);
+        for _ in 0..(width as usize * height as usize) {
+            pixels.extend_from_slice(&[background.r, background.g, background.b, 255]);
+        }
+        Raster {
+            width: width,
+            height: height,
+            pixels: pixels,
+            clip: (0, 0, width as i32, height as i32),
+        }
+    }
+
+    /// Limits drawing to the specified rectangle, or to the whole raster if `clip` is `None`.
+    pub fn set_clip(&mut self, clip: Option<Rect>) {
+        let (w, h) = (self.width as i32, self.height as i32);
+        self.clip = match clip {
+            Some(r) => (r.x.max(0), r.y.max(0), (r.x + r.width).min(w), (r.y + r.height).min(h)),
+            None => (0, 0, w, h),
+        };
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn into_pixels(self) -> Vec<u8> {
+        self.pixels
+    }
+
+    // Pixels outside the clip rectangle are ignored, so callers don't need to clip.
+    pub fn set_pixel(&mut self, x: i32, y: i32, color: Color) {
+        let (cx, cy, cr, cb) = self.clip;
+        if x < cx || y < cy || x >= cr || y >= cb {
+            return;
+        }
+        let i = (y as usize * self.width as usize + x as usize) * 4;
+        self.pixels[i] = color.r;
+        self.pixels[i + 1] = color.g;
+        self.pixels[i + 2] = color.b;
+    }
+
+    pub fn fill_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        let (cx, cy, cr, cb) = self.clip;
+        let x0 = x.max(cx);
+        let y0 = y.max(cy);
+        let x1 = (x + width).min(cr);
+        let y1 = (y + height).min(cb);
+        for py in y0..y1 {
+            for px in x0..x1 {
+                self.set_pixel(px, py, color);
+            }
+        }
+    }
+
+    pub fn draw_rect(&mut self, x: i32, y: i32, width: i32, height: i32, color: Color) {
+        self.fill_rect(x, y, width, 1, color);
+        self.fill_rect(x, y + height - 1, width, 1, color);
+        self.fill_rect(x, y, 1, height, color);
+        self.fill_rect(x + width - 1, y, 1, height, color);
+    }
+
+    // Draws a square dot of size `width` centered on the point.
+    fn draw_dot(&mut self, x: i32, y: i32, width: u32, color: Color) {
+        if width <= 1 {
+            self.set_pixel(x, y, color);
+        } else {
+            let w = width as i32;
+            self.fill_rect(x - (w - 1) / 2, y - (w - 1) / 2, w, w, color);
+        }
+    }
+
+    /// Draws a line from (x0, y0) to (x1, y1), including both end points, only drawing the pixels
+    /// for which `pattern` returns true. The pattern is passed a counter that increases by one
+    /// for each pixel along the line. The coordinates can be far outside the raster.
+    pub fn draw_line_pattern<F>(&mut self, x0: f64, y0: f64, x1: f64, y1: f64, width: u32,
+                                color: Color, pattern: &mut F)
+                                where F: FnMut(u32) -> bool {
+        // Points can be far off screen when zoomed in, so clip the line first to avoid iterating
+        // over millions of pixels that wouldn't be drawn.
+        if !(x0.is_finite() && y0.is_finite() && x1.is_finite() && y1.is_finite()) {
+            return;
+        }
+        let margin = width as f64 + 1.0;
+        let (cx, cy, cr, cb) = self.clip;
+        let clipped = clip_line(x0, y0, x1, y1,
+                                cx as f64 - margin, cy as f64 - margin,
+                                cr as f64 + margin, cb as f64 + margin);
+        let (x0, y0, x1, y1) = match clipped {
+            Some((x0, y0, x1, y1)) => (x0.round() as i32, y0.round() as i32,
+                                       x1.round() as i32, y1.round() as i32),
+            None => return,
+        };
+
+        // Bresenham's line algorithm
+        let dx = (x1 - x0).abs();
+        let dy = -(y1 - y0).abs();
+        let sx = if x0 < x1 { 1 } else { -1 };
+        let sy = if y0 < y1 { 1 } else { -1 };
+        let mut err = dx + dy;
+        let (mut x, mut y) = (x0, y0);
+        let mut counter = 0;
+        loop {
+            if pattern(counter) {
+                self.draw_dot(x, y, width, color);
+            }
+            counter += 1;
+            if x == x1 && y == y1 {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Draws text with its top left corner at (x, y). Characters that the built-in font doesn't
+    /// have are drawn as '?'.
+    pub fn draw_text(&mut self, x: i32, y: i32, text: &str, color: Color) {
+        for (i, c) in text.chars().enumerate() {
+            let rows = glyph(c);
+            let gx = x + i as i32 * GLYPH_ADVANCE;
+            for (row, bits) in rows.iter().enumerate() {
+                for col in 0..GLYPH_WIDTH {
+                    if bits & (1 << (GLYPH_WIDTH - 1 - col)) != 0 {
+                        self.set_pixel(gx + col, y + row as i32, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub fn text_width(text: &str) -> i32 {
+    let count = text.chars().count() as i32;
+    if count == 0 { 0 } else { count * GLYPH_ADVANCE - 1 }
+}
+
+// Cohen-Sutherland would work too, but Liang-Barsky is shorter.
+fn clip_line(x0: f64, y0: f64, x1: f64, y1: f64,
+             min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Option<(f64, f64, f64, f64)> {
+    let (dx, dy) = (x1 - x0, y1 - y0);
+    let mut t0 = 0.0f64;
+    let mut t1 = 1.0f64;
+    let checks = [(-dx, x0 - min_x), (dx, max_x - x0), (-dy, y0 - min_y), (dy, max_y - y0)];
+    for &(p, q) in checks.iter() {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else {
+            let r = q / p;
+            if p < 0.0 {
+                if r > t1 { return None; }
+                if r > t0 { t0 = r; }
+            } else {
+                if r < t0 { return None; }
+                if r < t1 { t1 = r; }
+            }
+        }
+    }
+    Some((x0 + t0 * dx, y0 + t0 * dy, x0 + t1 * dx, y0 + t1 * dy))
+}
+
+// Each glyph is seven rows of five pixels. The most significant of the five bits is the leftmost
+// pixel.
+fn glyph(c: char) -> [u8; 7] {
+    match c {
+        ' ' => [0, 0, 0, 0, 0, 0, 0],
+        '0' => [0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110],
+        '1' => [0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        '2' => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111],
+        '3' => [0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110],
+        '4' => [0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010],
+        '5' => [0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110],
+        '6' => [0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110],
+        '7' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000],
+        '8' => [0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110],
+        '9' => [0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100],
+        'A' => [0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'B' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110],
+        'C' => [0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110],
+        'D' => [0b11100, 0b10010, 0b10001, 0b10001, 0b10001, 0b10010, 0b11100],
+        'E' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111],
+        'F' => [0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000],
+        'G' => [0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111],
+        'H' => [0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001],
+        'I' => [0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'J' => [0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100],
+        'K' => [0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001],
+        'L' => [0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111],
+        'M' => [0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001],
+        'N' => [0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001],
+        'O' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'P' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000],
+        'Q' => [0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101],
+        'R' => [0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001],
+        'S' => [0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110],
+        'T' => [0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100],
+        'U' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110],
+        'V' => [0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'W' => [0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010],
+        'X' => [0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001],
+        'Y' => [0b10001, 0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100],
+        'Z' => [0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111],
+        'a' => [0, 0, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111],
+        'b' => [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b11110],
+        'c' => [0, 0, 0b01110, 0b10000, 0b10000, 0b10001, 0b01110],
+        'd' => [0b00001, 0b00001, 0b01101, 0b10011, 0b10001, 0b10001, 0b01111],
+        'e' => [0, 0, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110],
+        'f' => [0b00110, 0b01001, 0b01000, 0b11100, 0b01000, 0b01000, 0b01000],
+        'g' => [0, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110],
+        'h' => [0b10000, 0b10000, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001],
+        'i' => [0b00100, 0, 0b01100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'j' => [0b00010, 0, 0b00110, 0b00010, 0b00010, 0b10010, 0b01100],
+        'k' => [0b10000, 0b10000, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010],
+        'l' => [0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110],
+        'm' => [0, 0, 0b11010, 0b10101, 0b10101, 0b10001, 0b10001],
+        'n' => [0, 0, 0b10110, 0b11001, 0b10001, 0b10001, 0b10001],
+        'o' => [0, 0, 0b01110, 0b10001, 0b10001, 0b10001, 0b01110],
+        'p' => [0, 0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000],
+        'q' => [0, 0b01111, 0b10001, 0b10001, 0b01111, 0b00001, 0b00001],
+        'r' => [0, 0, 0b10110, 0b11001, 0b10000, 0b10000, 0b10000],
+        's' => [0, 0, 0b01111, 0b10000, 0b01110, 0b00001, 0b11110],
+        't' => [0b01000, 0b01000, 0b11100, 0b01000, 0b01000, 0b01001, 0b00110],
+        'u' => [0, 0, 0b10001, 0b10001, 0b10001, 0b10011, 0b01101],
+        'v' => [0, 0, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100],
+        'w' => [0, 0, 0b10001, 0b10001, 0b10101, 0b10101, 0b01010],
+        'x' => [0, 0, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001],
+        'y' => [0, 0b10001, 0b10001, 0b10001, 0b01111, 0b00001, 0b01110],
+        'z' => [0, 0, 0b11111, 0b00010, 0b00100, 0b01000, 0b11111],
+        '.' => [0, 0, 0, 0, 0, 0b01100, 0b01100],
+        ',' => [0, 0, 0, 0, 0b01100, 0b00100, 0b01000],
+        '-' => [0, 0, 0, 0b11111, 0, 0, 0],
+        '+' => [0, 0b00100, 0b00100, 0b11111, 0b00100, 0b00100, 0],
+        ':' => [0, 0b01100, 0b01100, 0, 0b01100, 0b01100, 0],
+        '(' => [0b00010, 0b00100, 0b01000, 0b01000, 0b01000, 0b00100, 0b00010],
+        ')' => [0b01000, 0b00100, 0b00010, 0b00010, 0b00010, 0b00100, 0b01000],
+        '[' => [0b01110, 0b01000, 0b01000, 0b01000, 0b01000, 0b01000, 0b01110],
+        ']' => [0b01110, 0b00010, 0b00010, 0b00010, 0b00010, 0b00010, 0b01110],
+        '<' => [0b00010, 0b00100, 0b01000, 0b10000, 0b01000, 0b00100, 0b00010],
+        '>' => [0b01000, 0b00100, 0b00010, 0b00001, 0b00010, 0b00100, 0b01000],
+        '/' => [0, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0],
+        '%' => [0b11000, 0b11001, 0b00010, 0b00100, 0b01000, 0b10011, 0b00011],
+        '_' => [0, 0, 0, 0, 0, 0, 0b11111],
+        '=' => [0, 0, 0b11111, 0, 0b11111, 0, 0],
+        '*' => [0, 0b00100, 0b10101, 0b01110, 0b10101, 0b00100, 0],
+        '#' => [0b01010, 0b01010, 0b11111, 0b01010, 0b11111, 0b01010, 0b01010],
+        '!' => [0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0, 0b00100],
+        '\'' => [0b01100, 0b00100, 0b01000, 0, 0, 0, 0],
+        _ => [0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0, 0b00100], // '?'
+    }
+}
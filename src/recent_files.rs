@@ -0,0 +1,213 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+use super::control_prelude::*;
+use std::cell::RefCell;
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::rc::{Rc, Weak};
+use super::{Config, Item, Menu};
+use super::containers::Container;
+
+// The title of the item shown when there are no recent files
+const EMPTY_TITLE: &'static str = "(Empty)";
+
+struct RecentFilesData {
+    // The most recently used file is first.
+    paths: Vec<PathBuf>,
+    max_count: usize,
+}
+
+/// Maintains a list of the most recently used files and shows it as the items of a menu, which
+/// is usually put in a "Recent Files" submenu. The menu should not be used for anything else
+/// because its items are replaced whenever the list changes.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use clear_coat::*;
+/// let config = Config::new("MyApp");
+/// let _ = config.load();
+/// let recent_menu = Menu::new();
+/// let recent = RecentFiles::new(&recent_menu, 8);
+/// recent.load(&config, "RecentFiles");
+/// recent.open_event().add(|path| println!("opening {}", path.display()));
+/// let file_menu = Menu::with_children(&[
+///     &Submenu::with_title_and_menu("Recent Files", &recent_menu),
+/// ]);
+/// ```
+#[derive(Clone)]
+pub struct RecentFiles {
+    menu: Menu,
+    data: Rc<RefCell<RecentFilesData>>,
+}
+
+impl RecentFiles {
+    /// Creates an empty list that keeps at most `max_count` files and shows them in `menu`.
+    pub fn new(menu: &Menu, max_count: usize) -> RecentFiles {
+        let recent = RecentFiles {
+            menu: menu.clone(),
+            data: Rc::new(RefCell::new(RecentFilesData { paths: vec![], max_count: max_count })),
+        };
+        recent.update_menu();
+        recent
+    }
+
+    /// Returns the files, most recently used first.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.data.borrow().paths.clone()
+    }
+
+    /// Moves a file to the top of the list, adding it if it isn't already in the list. If the
+    /// list is full, the least recently used file is removed. Call this when a file is opened or
+    /// saved.
+    pub fn add(&self, path: &Path) {
+        {
+            let mut data = self.data.borrow_mut();
+            data.paths.retain(|p| p != path);
+            data.paths.insert(0, path.to_owned());
+            let max_count = data.max_count;
+            data.paths.truncate(max_count);
+        }
+        self.update_menu();
+    }
+
+    /// Removes a file from the list, such as when it couldn't be opened because it was deleted.
+    pub fn remove(&self, path: &Path) {
+        self.data.borrow_mut().paths.retain(|p| p != path);
+        self.update_menu();
+    }
+
+    pub fn clear(&self) {
+        self.data.borrow_mut().paths.clear();
+        self.update_menu();
+    }
+
+    pub fn max_count(&self) -> usize {
+        self.data.borrow().max_count
+    }
+
+    /// Sets the maximum number of files in the list, removing the least recently used files if
+    /// there are more.
+    pub fn set_max_count(&self, max_count: usize) {
+        {
+            let mut data = self.data.borrow_mut();
+            data.max_count = max_count;
+            data.paths.truncate(max_count);
+        }
+        self.update_menu();
+    }
+
+    /// Replaces the list with the files saved in the group `group` of the config by `save`.
+    pub fn load(&self, config: &Config, group: &str) {
+        {
+            let mut data = self.data.borrow_mut();
+            let max_count = data.max_count;
+            data.paths = (1..max_count + 1)
+                .filter_map(|i| config.string(group, &format!("File{}", i)))
+                .filter(|s| !s.is_empty())
+                .map(PathBuf::from)
+                .collect();
+        }
+        self.update_menu();
+    }
+
+    /// Saves the list in the group `group` of the config. Call `Config::save` to write it to the
+    /// config file.
+    pub fn save(&self, config: &Config, group: &str) {
+        let data = self.data.borrow();
+        for i in 0..data.max_count {
+            // Entries past the end of the list are cleared in case a longer list was saved
+            // before.
+            let value = data.paths.get(i).map_or(String::new(), |p| p.to_string_lossy().into_owned());
+            config.set_string(group, &format!("File{}", i + 1), &value);
+        }
+    }
+
+    /// Called when a file is chosen from the menu, with its path.
+    pub fn open_event<'a>(&'a self) -> Event<'a, FnMut(&Path), RecentFileCallbackToken> {
+        Event::new(&self.menu as &Control, &RECENT_FILE_CALLBACKS)
+    }
+
+    // Makes the menu have one item per file. Existing items are reused so that an item isn't
+    // destroyed while its action callback is running, which happens when a file is chosen and
+    // then added to the list again.
+    fn update_menu(&self) {
+        let titles: Vec<String> = self.data.borrow().paths.iter().enumerate().map(|(i, path)| {
+            // IUP uses "&" to mark a mnemonic, so a literal "&" in a path has to be doubled.
+            let path = path.display().to_string().replace('&', "&&");
+            // Give the first nine items a number as a mnemonic.
+            if i < 9 {
+                format!("&{} {}", i + 1, path)
+            } else {
+                path
+            }
+        }).collect();
+        let item_count = titles.len().max(1);
+
+        let menu_ih = self.menu.handle();
+        unsafe {
+            while self.menu.child_count() > item_count {
+                let last = IupGetChild(menu_ih, (self.menu.child_count() - 1) as c_int);
+                // Dropping the reference destroys the item since it has no parent and no wrapper.
+                let last = HandleRc::new(last);
+                IupDetach(last.get());
+            }
+            while self.menu.child_count() < item_count {
+                let index = self.menu.child_count();
+                let item = Item::new();
+                let weak_data = Rc::downgrade(&self.data);
+                item.action_event().add(move || choose_file(menu_ih, &weak_data, index));
+                self.menu.append(&item).expect("failed to append recent file item");
+                if !get_attribute_ptr(menu_ih, "WID\0").is_null() {
+                    IupMap(item.handle());
+                }
+            }
+        }
+
+        for i in 0..item_count {
+            let item = unsafe { IupGetChild(menu_ih, i as c_int) };
+            match titles.get(i) {
+                Some(title) => {
+                    set_str_attribute(item, "TITLE\0", title);
+                    set_str_attribute(item, "ACTIVE\0", "YES\0");
+                },
+                None => {
+                    set_str_attribute(item, "TITLE\0", EMPTY_TITLE);
+                    set_str_attribute(item, "ACTIVE\0", "NO\0");
+                },
+            }
+        }
+    }
+}
+
+// Notifies the open event of the file at `index` in the list.
+fn choose_file(menu_ih: *mut Ihandle, weak_data: &Weak<RefCell<RecentFilesData>>, index: usize) {
+    let path = match weak_data.upgrade() {
+        Some(data) => data.borrow().paths.get(index).cloned(),
+        None => None,
+    };
+    if let Some(path) = path {
+        with_callbacks(menu_ih, &RECENT_FILE_CALLBACKS, |cbs| {
+            for cb in cbs {
+                (&mut *cb.1.borrow_mut())(&path);
+            }
+            IUP_DEFAULT
+        });
+    }
+}
+
+// The open event isn't an IUP callback, so this registry's callback is only used to store the
+// callbacks. The event is notified by `choose_file`.
+callback_token!(RecentFileCallbackToken);
+thread_local!(
+    static RECENT_FILE_CALLBACKS: CallbackRegistry<FnMut(&Path), RecentFileCallbackToken> =
+        CallbackRegistry::new("_CLEAR_COAT_RECENT_FILE_CB\0", unsafe { mem::transmute::<_, Icallback>(recent_file_cb as usize) })
+);
+extern fn recent_file_cb(_ih: *mut Ihandle) -> c_int {
+    IUP_DEFAULT
+}
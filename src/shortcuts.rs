@@ -0,0 +1,341 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+use super::control_prelude::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt;
+use std::mem;
+use std::rc::{Rc, Weak};
+use super::{Button, Dialog, Item};
+use super::button::click_button;
+use super::handle_rc::add_ldestroy_callback;
+use super::menu::choose_item;
+
+// IUP key codes and modifier flags from iupkey.h, which iup-sys doesn't have
+const K_BS: u32 = 0x08;
+const K_TAB: u32 = 0x09;
+const K_CR: u32 = 0x0D;
+const K_SP: u32 = 0x20;
+const K_ESC: u32 = 0xFF1B;
+const K_HOME: u32 = 0xFF50;
+const K_LEFT: u32 = 0xFF51;
+const K_UP: u32 = 0xFF52;
+const K_RIGHT: u32 = 0xFF53;
+const K_DOWN: u32 = 0xFF54;
+const K_PGUP: u32 = 0xFF55;
+const K_PGDN: u32 = 0xFF56;
+const K_END: u32 = 0xFF57;
+const K_INS: u32 = 0xFF63;
+const K_DEL: u32 = 0xFFFF;
+const K_F1: u32 = 0xFFBE;
+const SHIFT_FLAG: u32 = 0x10000000;
+const CTRL_FLAG: u32 = 0x20000000;
+const ALT_FLAG: u32 = 0x40000000;
+const SYS_FLAG: u32 = 0x80000000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Key {
+    /// A key that types a character. Letters are the same key whether they are uppercase or
+    /// lowercase. For other characters, use the character typed with the modifiers held, such as
+    /// `'+'` instead of Shift with `'='`.
+    Char(char),
+    /// A function key from F1 to F12.
+    F(u8),
+    Space,
+    Enter,
+    Tab,
+    Backspace,
+    Escape,
+    Insert,
+    Delete,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+impl Key {
+    fn code(self) -> u32 {
+        match self {
+            Key::Char(c) => c as u32,
+            Key::F(n) => {
+                assert!(n >= 1 && n <= 12, "function key must be from F1 to F12");
+                K_F1 + n as u32 - 1
+            },
+            Key::Space => K_SP,
+            Key::Enter => K_CR,
+            Key::Tab => K_TAB,
+            Key::Backspace => K_BS,
+            Key::Escape => K_ESC,
+            Key::Insert => K_INS,
+            Key::Delete => K_DEL,
+            Key::Home => K_HOME,
+            Key::End => K_END,
+            Key::PageUp => K_PGUP,
+            Key::PageDown => K_PGDN,
+            Key::Left => K_LEFT,
+            Key::Right => K_RIGHT,
+            Key::Up => K_UP,
+            Key::Down => K_DOWN,
+        }
+    }
+}
+
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Key::Char(c) => write!(f, "{}", c),
+            Key::F(n) => write!(f, "F{}", n),
+            Key::Space => f.write_str("Space"),
+            Key::Enter => f.write_str("Enter"),
+            Key::Tab => f.write_str("Tab"),
+            Key::Backspace => f.write_str("Backspace"),
+            Key::Escape => f.write_str("Esc"),
+            Key::Insert => f.write_str("Ins"),
+            Key::Delete => f.write_str("Del"),
+            Key::Home => f.write_str("Home"),
+            Key::End => f.write_str("End"),
+            Key::PageUp => f.write_str("PgUp"),
+            Key::PageDown => f.write_str("PgDn"),
+            Key::Left => f.write_str("Left"),
+            Key::Right => f.write_str("Right"),
+            Key::Up => f.write_str("Up"),
+            Key::Down => f.write_str("Down"),
+        }
+    }
+}
+
+/// A key pressed while holding modifier keys, like Ctrl+S.
+///
+/// Formatting a chord gives the text shown in menus, such as `"Ctrl+Shift+S"`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct KeyChord {
+    key: Key,
+    ctrl: bool,
+    shift: bool,
+    alt: bool,
+    sys: bool,
+}
+
+impl KeyChord {
+    pub fn new(key: Key) -> KeyChord {
+        let key = match key {
+            Key::Char(c) => Key::Char(c.to_ascii_uppercase()),
+            key => key,
+        };
+        KeyChord { key: key, ctrl: false, shift: false, alt: false, sys: false }
+    }
+
+    pub fn ctrl(mut self) -> KeyChord {
+        self.ctrl = true;
+        self
+    }
+
+    pub fn shift(mut self) -> KeyChord {
+        self.shift = true;
+        self
+    }
+
+    pub fn alt(mut self) -> KeyChord {
+        self.alt = true;
+        self
+    }
+
+    /// Adds the Windows key, or the Command key on a Mac.
+    pub fn sys(mut self) -> KeyChord {
+        self.sys = true;
+        self
+    }
+
+    pub fn key(&self) -> Key {
+        self.key
+    }
+
+    // Returns the code IUP passes to K_ANY when the chord is pressed.
+    fn code(&self) -> u32 {
+        let other_modifiers = self.ctrl || self.alt || self.sys;
+        let mut code = match self.key {
+            // Without other modifiers, shift only changes the case of a letter.
+            Key::Char(c) if c.is_ascii_alphabetic() && !other_modifiers && !self.shift => {
+                c.to_ascii_lowercase() as u32
+            },
+            key => key.code(),
+        };
+        let is_char = match self.key { Key::Char(_) => true, _ => false };
+        if self.shift && (other_modifiers || !is_char) { code |= SHIFT_FLAG; }
+        if self.ctrl { code |= CTRL_FLAG; }
+        if self.alt { code |= ALT_FLAG; }
+        if self.sys { code |= SYS_FLAG; }
+        code
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ctrl { try!(f.write_str("Ctrl+")); }
+        if self.alt { try!(f.write_str("Alt+")); }
+        if self.shift { try!(f.write_str("Shift+")); }
+        if self.sys { try!(f.write_str(if cfg!(target_os = "macos") { "Cmd+" } else { "Win+" })); }
+        write!(f, "{}", self.key)
+    }
+}
+
+#[derive(Clone)]
+enum Binding {
+    Action(Rc<RefCell<FnMut()>>),
+    Item(Item),
+    Button(Button),
+}
+
+struct ShortcutsData {
+    // Keyed by the IUP key code of the chord
+    bindings: HashMap<u32, (KeyChord, Binding)>,
+}
+
+// The registry of each dialog is kept alive by the dialog's K_ANY callback, so the map only holds
+// weak references.
+thread_local!(
+    static DIALOG_SHORTCUTS: RefCell<HashMap<*mut Ihandle, Weak<RefCell<ShortcutsData>>>> =
+        RefCell::new(HashMap::new())
+);
+
+/// The keyboard shortcuts of a dialog. Pressing a bound key chord while any control in the dialog
+/// has the focus runs the binding instead of passing the key to the control.
+///
+/// A binding can be a closure, or an `Item` or `Button` that is chosen or clicked as if the
+/// user did it. Inactive items and buttons are skipped. Binding a chord that is already bound
+/// replaces the old binding.
+///
+/// # Examples
+///
+/// ```no_run
+/// # use clear_coat::*;
+/// let save = Item::with_title("&Save");
+/// let dialog = Dialog::new();
+/// // The title of the item becomes "&Save\tCtrl+S".
+/// dialog.shortcuts().bind_item(KeyChord::new(Key::Char('s')).ctrl(), &save);
+/// ```
+#[derive(Clone)]
+pub struct Shortcuts {
+    data: Rc<RefCell<ShortcutsData>>,
+}
+
+impl Shortcuts {
+    /// Binds the chord to a closure.
+    pub fn bind<F: FnMut() + 'static>(&self, chord: KeyChord, action: F) -> &Self {
+        self.insert(chord, Binding::Action(Rc::new(RefCell::new(action))));
+        self
+    }
+
+    /// Binds the chord to choosing the item. The chord is added to the item's title after a tab
+    /// so that the menu shows it, replacing any text already after a tab.
+    pub fn bind_item(&self, chord: KeyChord, item: &Item) -> &Self {
+        set_accelerator_text(item, Some(chord));
+        self.insert(chord, Binding::Item(item.clone()));
+        self
+    }
+
+    /// Binds the chord to clicking the button.
+    pub fn bind_button(&self, chord: KeyChord, button: &Button) -> &Self {
+        self.insert(chord, Binding::Button(button.clone()));
+        self
+    }
+
+    /// Removes the binding of the chord. If it was bound to an item, the chord is removed from
+    /// the item's title.
+    pub fn unbind(&self, chord: KeyChord) -> &Self {
+        let old = self.data.borrow_mut().bindings.remove(&chord.code());
+        if let Some((_, Binding::Item(item))) = old {
+            set_accelerator_text(&item, None);
+        }
+        self
+    }
+
+    pub fn is_bound(&self, chord: KeyChord) -> bool {
+        self.data.borrow().bindings.contains_key(&chord.code())
+    }
+
+    // Replaces any binding the chord already has. If the old binding was an item, its title no
+    // longer shows the chord unless the new binding is the same item.
+    fn insert(&self, chord: KeyChord, binding: Binding) {
+        let old = self.data.borrow_mut().bindings.insert(chord.code(), (chord, binding));
+        if let Some((_, Binding::Item(old_item))) = old {
+            let still_bound = match self.data.borrow().bindings.get(&chord.code()) {
+                Some(&(_, Binding::Item(ref item))) => item.handle() == old_item.handle(),
+                _ => false,
+            };
+            if !still_bound {
+                set_accelerator_text(&old_item, None);
+            }
+        }
+    }
+}
+
+fn set_accelerator_text(item: &Item, chord: Option<KeyChord>) {
+    let title = item.title();
+    let text = title.split('\t').next().unwrap_or("");
+    match chord {
+        Some(chord) => item.set_title(&format!("{}\t{}", text, chord)),
+        None => item.set_title(text),
+    };
+}
+
+fn run_shortcut(data: &RefCell<ShortcutsData>, code: u32) -> CallbackAction {
+    // The binding is cloned so that it can change the shortcuts.
+    let binding = match data.borrow().bindings.get(&code) {
+        Some(&(_, ref binding)) => binding.clone(),
+        None => return CallbackAction::Default,
+    };
+    match binding {
+        Binding::Action(action) => (&mut *action.borrow_mut())(),
+        Binding::Item(item) => choose_item(&item),
+        Binding::Button(button) => click_button(&button),
+    }
+    // The key was handled, so it isn't passed on to the control with the focus.
+    CallbackAction::Ignore
+}
+
+// Returns the shortcuts of the dialog, creating them the first time.
+pub fn dialog_shortcuts(dialog: &Dialog) -> Shortcuts {
+    let ih = dialog.handle();
+    let existing = DIALOG_SHORTCUTS.with(|map| map.borrow().get(&ih).and_then(|data| data.upgrade()));
+    if let Some(data) = existing {
+        return Shortcuts { data: data };
+    }
+
+    let data = Rc::new(RefCell::new(ShortcutsData { bindings: HashMap::new() }));
+    let data2 = data.clone();
+    SHORTCUT_KEY_CALLBACKS.with(|reg| reg.add_callback(ih, move |code| run_shortcut(&data2, code)));
+    DIALOG_SHORTCUTS.with(|map| map.borrow_mut().insert(ih, Rc::downgrade(&data)));
+    add_ldestroy_callback(ih, |ih| {
+        DIALOG_SHORTCUTS.with(|map| map.borrow_mut().remove(&ih));
+    });
+    Shortcuts { data: data }
+}
+
+callback_token!(ShortcutKeyCallbackToken);
+thread_local!(
+    static SHORTCUT_KEY_CALLBACKS: CallbackRegistry<FnMut(u32) -> CallbackAction, ShortcutKeyCallbackToken> =
+        CallbackRegistry::new("K_ANY\0", unsafe { mem::transmute::<_, Icallback>(shortcut_key_cb as usize) })
+);
+extern fn shortcut_key_cb(ih: *mut Ihandle, c: c_int) -> c_int {
+    with_callbacks(ih, &SHORTCUT_KEY_CALLBACKS, |cbs| {
+        let mut action = CallbackAction::Default;
+        for cb in cbs {
+            match (&mut *cb.1.borrow_mut())(c as u32) {
+                CallbackAction::Default => {},
+                cb_action => action = cb_action,
+            }
+        }
+        action.to_int()
+    })
+}
@@ -0,0 +1,78 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+use super::control_prelude::*;
+use super::Config;
+use super::containers::{
+    Container,
+    NonDialogContainer,
+};
+
+/// A container with two children separated by a bar the user can drag to change how much space
+/// each child gets. With the default vertical orientation, the children are side by side.
+#[derive(Clone)]
+pub struct Split(HandleRc);
+
+impl Split {
+    pub fn new() -> Self {
+        unsafe {
+            ::iup_open();
+            let ih = IupSplit(ptr::null_mut(), ptr::null_mut());
+            Split(HandleRc::new(ih))
+        }
+    }
+
+    pub fn with_children(first: &Control, second: &Control) -> Self {
+        unsafe {
+            ::iup_open();
+            let ih = IupSplit(first.handle(), second.handle());
+            Split(HandleRc::new(ih))
+        }
+    }
+
+    /// Returns the fraction of the space the first child gets, from 0.0 to 1.0.
+    pub fn ratio(&self) -> f32 {
+        unsafe {
+            let s = get_str_attribute_slice(self.handle(), "VALUE\0");
+            s.parse::<f32>().expect("could not convert VALUE to a number") / 1000.0
+        }
+    }
+
+    pub fn set_ratio(&self, ratio: f32) -> &Self {
+        let value = (ratio.max(0.0).min(1.0) * 1000.0).round() as i32;
+        set_str_attribute(self.handle(), "VALUE\0", &format!("{}\0", value));
+        self
+    }
+
+    /// Saves the ratio in the group `name` of the config.
+    pub fn save_ratio(&self, config: &Config, name: &str) {
+        config.set_float(name, "Ratio", self.ratio() as f64);
+    }
+
+    /// Sets the ratio to the one saved by `save_ratio`, if there is one. A saved ratio that isn't
+    /// between 0.0 and 1.0 is ignored.
+    pub fn restore_ratio(&self, config: &Config, name: &str) -> &Self {
+        match config.float(name, "Ratio") {
+            Some(ratio) if ratio >= 0.0 && ratio <= 1.0 => { self.set_ratio(ratio as f32); },
+            _ => {},
+        }
+        self
+    }
+}
+
+impl_control_traits!(Split);
+
+impl Container for Split {}
+impl NonDialogContainer for Split {}
+
+impl ActiveAttribute for Split {}
+impl ExpandAttribute for Split {}
+impl MinMaxSizeAttribute for Split {}
+impl OrientationAttribute for Split {}
+impl VisibleAttribute for Split {}
+
+impl MenuCommonCallbacks for Split {}
@@ -10,11 +10,50 @@ use super::containers::{
     Container,
     NonDialogContainer,
 };
+use super::Image;
+use super::extra_refs::{ExtraRefKey, add_extra_ref, remove_extra_ref};
 
+// IUP stores a tab's image on its child, so the reference is kept by the child and moves with it.
+const EXTRA_REF_TAB_IMAGE: ExtraRefKey = ExtraRefKey(6);
+
+/// The side of a `Tabs` control that the tabs are shown on.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TabPosition {
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+impl TabPosition {
+    fn from_str(s: &str) -> TabPosition {
+        match s {
+            "BOTTOM" => TabPosition::Bottom,
+            "LEFT" => TabPosition::Left,
+            "RIGHT" => TabPosition::Right,
+            _ => TabPosition::Top,
+        }
+    }
+
+    fn to_str(self) -> &'static str {
+        match self {
+            TabPosition::Top => "TOP\0",
+            TabPosition::Bottom => "BOTTOM\0",
+            TabPosition::Left => "LEFT\0",
+            TabPosition::Right => "RIGHT\0",
+        }
+    }
+}
+
+/// Describes a tab to add with `Tabs::append_tabs` or `Tabs::insert_tab`.
 #[derive(Clone)]
 pub struct TabInfo<'a, 'b> {
     title: &'a str,
     control: &'b Control,
+    image: Option<&'b Image>,
+    tip: Option<&'a str>,
+    visible: bool,
+    active: bool,
 }
 
 impl<'a, 'b> TabInfo<'a, 'b> {
@@ -22,6 +61,10 @@ impl<'a, 'b> TabInfo<'a, 'b> {
         TabInfo {
             title: "",
             control: control,
+            image: None,
+            tip: None,
+            visible: true,
+            active: true,
         }
     }
 
@@ -29,8 +72,30 @@ impl<'a, 'b> TabInfo<'a, 'b> {
         self.title = title;
         self
     }
+
+    pub fn image(&mut self, image: &'b Image) -> &mut Self {
+        self.image = Some(image);
+        self
+    }
+
+    pub fn tip(&mut self, tip: &'a str) -> &mut Self {
+        self.tip = Some(tip);
+        self
+    }
+
+    pub fn visible(&mut self, visible: bool) -> &mut Self {
+        self.visible = visible;
+        self
+    }
+
+    pub fn active(&mut self, active: bool) -> &mut Self {
+        self.active = active;
+        self
+    }
 }
 
+/// Tab indexes are zero-based.
+///
 /// # Examples
 ///
 /// Using the `append_tabs` helper:
@@ -77,15 +142,213 @@ impl Tabs {
         self
     }
 
+    /// Sets the image shown next to a tab's title, or removes it if `image` is `None`. The tab's
+    /// control keeps a reference to the image.
+    pub fn set_tab_image(&self, pos: usize, image: Option<&Image>) -> &Self {
+        let child = self.child_handle(pos);
+        remove_extra_ref(child, EXTRA_REF_TAB_IMAGE);
+        let attr = format!("TABIMAGE{}\0", pos);
+        match image {
+            Some(image) => unsafe {
+                set_attribute_handle(self.handle(), &attr, image.handle());
+                add_extra_ref(child, EXTRA_REF_TAB_IMAGE, HandleRc::new(image.handle()));
+            },
+            None => reset_attribute(self.handle(), &attr),
+        }
+        self
+    }
+
+    pub fn tab_tip(&self, pos: usize) -> String {
+        get_str_attribute(self.handle(), &format!("TABTIP{}\0", pos))
+    }
+
+    /// Sets the text shown when the mouse hovers over a tab.
+    pub fn set_tab_tip(&self, pos: usize, tip: &str) -> &Self {
+        set_str_attribute(self.handle(), &format!("TABTIP{}\0", pos), tip);
+        self
+    }
+
+    /// Returns false if a tab is disabled. A disabled tab can't be made current by the user.
+    pub fn tab_active(&self, pos: usize) -> bool {
+        unsafe {
+            let attr = format!("TABACTIVE{}\0", pos);
+            get_str_attribute_slice(self.handle(), &attr) != "NO"
+        }
+    }
+
+    pub fn set_tab_active(&self, pos: usize, active: bool) -> &Self {
+        set_str_attribute(self.handle(), &format!("TABACTIVE{}\0", pos), if active { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Returns false if a tab is hidden. The tab's control still exists, but the tab isn't shown.
+    pub fn tab_visible(&self, pos: usize) -> bool {
+        unsafe {
+            let attr = format!("TABVISIBLE{}\0", pos);
+            get_str_attribute_slice(self.handle(), &attr) != "NO"
+        }
+    }
+
+    pub fn set_tab_visible(&self, pos: usize, visible: bool) -> &Self {
+        set_str_attribute(self.handle(), &format!("TABVISIBLE{}\0", pos), if visible { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    /// Returns the index of the current tab, or `None` if there are no tabs.
+    pub fn current_tab(&self) -> Option<usize> {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "VALUEPOS\0").parse().ok()
+        }
+    }
+
+    pub fn set_current_tab(&self, pos: usize) -> &Self {
+        set_str_attribute(self.handle(), "VALUEPOS\0", &format!("{}\0", pos));
+        self
+    }
+
+    /// Returns the handle of the current tab's control, or `None` if there are no tabs. The
+    /// handle can be compared with a child's `handle()`.
+    pub fn current_tab_control(&self) -> Option<*mut Ihandle> {
+        let child = get_attribute_ptr(self.handle(), "VALUE_HANDLE\0") as *mut Ihandle;
+        if child.is_null() { None } else { Some(child) }
+    }
+
+    /// Makes the tab of the specified control current. The control must be a child of the tabs.
+    pub fn set_current_tab_control(&self, control: &Control) -> &Self {
+        assert!(self.tab_index(control).is_some(), "control is not a child of the tabs");
+        unsafe {
+            // VALUE_HANDLE takes the child's handle itself, not the name of a handle.
+            IupSetAttribute(self.handle(), "VALUE_HANDLE\0".as_ptr() as *const c_char,
+                            control.handle() as *const c_char);
+        }
+        self
+    }
+
+    /// Returns the index of the tab of the specified control, or `None` if it isn't a child of
+    /// the tabs.
+    pub fn tab_index(&self, control: &Control) -> Option<usize> {
+        let pos = unsafe { IupGetChildPos(self.handle(), control.handle()) };
+        if pos < 0 { None } else { Some(pos as usize) }
+    }
+
+    pub fn tab_position(&self) -> TabPosition {
+        unsafe {
+            TabPosition::from_str(&get_str_attribute_slice(self.handle(), "TABTYPE\0"))
+        }
+    }
+
+    /// Sets which side the tabs are shown on. It can only be changed before the control is
+    /// mapped.
+    pub fn set_tab_position(&self, position: TabPosition) -> &Self {
+        set_str_attribute(self.handle(), "TABTYPE\0", position.to_str());
+        self
+    }
+
+    pub fn shows_close(&self) -> bool {
+        unsafe {
+            get_str_attribute_slice(self.handle(), "SHOWCLOSE\0") == "YES"
+        }
+    }
+
+    /// Shows a close button on each tab. Clicking it calls the `tab_close_event` callbacks.
+    pub fn set_show_close(&self, show: bool) -> &Self {
+        set_str_attribute(self.handle(), "SHOWCLOSE\0", if show { "YES\0" } else { "NO\0" });
+        self
+    }
+
+    fn child_handle(&self, pos: usize) -> *mut Ihandle {
+        let child = unsafe { IupGetChild(self.handle(), pos as c_int) };
+        assert!(child != ptr::null_mut(), "tab index out of bounds");
+        child
+    }
+
     pub fn append_tabs(&self, info: &[&TabInfo]) -> &Self {
         let mut index = self.child_count();
         for ti in info {
-            self.append(ti.control).expect("failed to append tab");
-            self.set_tab_title(index, ti.title);
+            self.insert_tab(index, ti);
             index += 1;
         }
         self
     }
+
+    /// Inserts a tab so that it has the index `pos`. A `pos` equal to the number of tabs appends
+    /// the tab. The tabs keep the tab's control alive, like any other container.
+    pub fn insert_tab(&self, pos: usize, info: &TabInfo) -> &Self {
+        let count = self.child_count();
+        assert!(pos <= count, "tab index out of bounds");
+        unsafe {
+            // IupInsert with a null reference child inserts at the front, so appending has to use
+            // IupAppend.
+            let parent = if pos == count {
+                IupAppend(self.handle(), info.control.handle())
+            } else {
+                IupInsert(self.handle(), self.child_handle(pos), info.control.handle())
+            };
+            if parent.is_null() {
+                panic!("failed to insert tab");
+            }
+            // A control added after the tabs are shown has to be mapped separately.
+            if !get_attribute_ptr(self.handle(), "WID\0").is_null() {
+                IupMap(info.control.handle());
+                IupRefresh(self.handle());
+            }
+        }
+        self.set_tab_title(pos, info.title);
+        if let Some(image) = info.image {
+            self.set_tab_image(pos, Some(image));
+        }
+        if let Some(tip) = info.tip {
+            self.set_tab_tip(pos, tip);
+        }
+        if !info.visible {
+            self.set_tab_visible(pos, false);
+        }
+        if !info.active {
+            self.set_tab_active(pos, false);
+        }
+        self
+    }
+
+    /// Removes a tab. Its control is destroyed unless there is still a wrapper referencing it,
+    /// in which case it can be added to a container again.
+    pub fn remove_tab(&self, pos: usize) -> &Self {
+        let child = self.child_handle(pos);
+        unsafe {
+            // Taking a reference before detaching means dropping it destroys the control if
+            // nothing else references it, the same as if a wrapper had been dropped.
+            let child = HandleRc::new(child);
+            IupDetach(child.get());
+            IupRefresh(self.handle());
+        }
+        self
+    }
+
+    /// Moves the tab at index `from` so that it has the index `to`. The tab's title, image, and
+    /// other attributes move with it.
+    pub fn move_tab(&self, from: usize, to: usize) -> &Self {
+        let count = self.child_count();
+        assert!(from < count && to < count, "tab index out of bounds");
+        if from == to {
+            return self;
+        }
+        let child = self.child_handle(from);
+        // The tab is inserted before the tab currently at `to`, or after it when moving right.
+        let ref_child = if to < from {
+            self.child_handle(to)
+        } else if to + 1 < count {
+            self.child_handle(to + 1)
+        } else {
+            ptr::null_mut()
+        };
+        unsafe {
+            // Reparenting doesn't destroy the control or change its references.
+            if IupReparent(child, self.handle(), ref_child) != IUP_NOERROR {
+                panic!("failed to move tab");
+            }
+            IupRefresh(self.handle());
+        }
+        self
+    }
 }
 
 impl_control_traits!(Tabs);
@@ -99,6 +362,86 @@ impl MinMaxSizeAttribute for Tabs {}
 impl TipAttribute for Tabs {}
 impl VisibleAttribute for Tabs {}
 
+#[derive(Clone)]
+pub struct TabChangeArgs {
+    /// The index of the tab that became current.
+    pub new_index: usize,
+    /// The index of the tab that was current, or `None` if there wasn't one.
+    pub old_index: Option<usize>,
+    _dummy: (),
+}
+
+impl_callbacks! {
+    Tabs {
+        "TABCHANGE_CB\0" => tab_change_event {
+            TAB_CHANGE_CALLBACKS<FnMut(&TabChangeArgs), TabChangeCallbackToken>
+        }
+        unsafe extern fn tab_change_cb(ih: *mut Ihandle, new_tab: *mut Ihandle, old_tab: *mut Ihandle) -> c_int {
+            with_callbacks(ih, &TAB_CHANGE_CALLBACKS, |cbs| {
+                let old_pos = if old_tab.is_null() { -1 } else { IupGetChildPos(ih, old_tab) };
+                let args = TabChangeArgs {
+                    new_index: IupGetChildPos(ih, new_tab) as usize,
+                    old_index: if old_pos < 0 { None } else { Some(old_pos as usize) },
+                    _dummy: (),
+                };
+                for cb in cbs {
+                    (&mut *cb.1.borrow_mut())(&args);
+                }
+                IUP_DEFAULT
+            })
+        }
+
+        // IUP only calls this when there are no `tab_change_event` callbacks.
+        "TABCHANGEPOS_CB\0" => tab_change_pos_event {
+            TAB_CHANGE_POS_CALLBACKS<FnMut(&TabChangeArgs), TabChangePosCallbackToken>
+        }
+        unsafe extern fn tab_change_pos_cb(ih: *mut Ihandle, new_pos: c_int, old_pos: c_int) -> c_int {
+            with_callbacks(ih, &TAB_CHANGE_POS_CALLBACKS, |cbs| {
+                let args = TabChangeArgs {
+                    new_index: new_pos as usize,
+                    old_index: if old_pos < 0 { None } else { Some(old_pos as usize) },
+                    _dummy: (),
+                };
+                for cb in cbs {
+                    (&mut *cb.1.borrow_mut())(&args);
+                }
+                IUP_DEFAULT
+            })
+        }
+
+        // Called when a tab's close button is clicked. Returning `Continue` removes the tab and
+        // destroys its control, `Default` hides the tab, and `Ignore` leaves it open.
+        "TABCLOSE_CB\0" => tab_close_event {
+            TAB_CLOSE_CALLBACKS<FnMut(usize) -> CallbackAction, TabCloseCallbackToken>
+        }
+        unsafe extern fn tab_close_cb(ih: *mut Ihandle, pos: c_int) -> c_int {
+            with_callbacks(ih, &TAB_CLOSE_CALLBACKS, |cbs| {
+                let mut action = CallbackAction::Default;
+                for cb in cbs {
+                    match (&mut *cb.1.borrow_mut())(pos as usize) {
+                        CallbackAction::Default => {},
+                        cb_action => action = cb_action,
+                    }
+                }
+                action.to_int()
+            })
+        }
+
+        // Called when a tab is right-clicked, with the index of the tab.
+        "RIGHTCLICK_CB\0" => right_click_event {
+            RIGHT_CLICK_CALLBACKS<FnMut(usize), RightClickCallbackToken>
+        }
+        unsafe extern fn right_click_cb(ih: *mut Ihandle, pos: c_int) -> c_int {
+            with_callbacks(ih, &RIGHT_CLICK_CALLBACKS, |cbs| {
+                for cb in cbs {
+                    (&mut *cb.1.borrow_mut())(pos as usize);
+                }
+                IUP_DEFAULT
+            })
+        }
+    }
+}
+
 impl MenuCommonCallbacks for Tabs {}
 impl GetKillFocusCallbacks for Tabs {}
 impl EnterLeaveWindowCallbacks for Tabs {}
@@ -73,6 +73,8 @@ impl_control_traits!(Text);
 
 impl ActiveAttribute for Text {}
 impl CanFocusAttribute for Text {}
+impl DropFilesTargetAttribute for Text {}
+impl DragDropAttribute for Text {}
 impl ExpandAttribute for Text {}
 impl MinMaxSizeAttribute for Text {}
 impl ScrollbarAttribute for Text {}
@@ -84,6 +86,9 @@ impl MenuCommonCallbacks for Text {}
 impl GetKillFocusCallbacks for Text {}
 impl EnterLeaveWindowCallbacks for Text {}
 impl ValueChangedCallback for Text {}
+impl DropFilesCallback for Text {}
+impl DragSourceCallbacks for Text {}
+impl DropTargetCallbacks for Text {}
 
 #[derive(Clone)]
 pub struct CaretArgs {
@@ -0,0 +1,33 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_clipboard() {
+    let clipboard = Clipboard::new();
+    clipboard.set_text("Clear Coat");
+    assert!(clipboard.is_text_available());
+    assert_eq!(clipboard.text(), Some("Clear Coat".to_owned()));
+
+    clipboard.register_format("application/x-clear-coat-test");
+    clipboard.set_format_data("application/x-clear-coat-test", &[1, 2, 0, 3]);
+    assert!(clipboard.is_format_available("application/x-clear-coat-test"));
+    assert_eq!(clipboard.format_data("application/x-clear-coat-test"), Some(vec![1, 2, 0, 3]));
+
+    let image = Image::with_rgba(3, 2, &[255; 3 * 2 * 4]);
+    clipboard.set_image(&image);
+    assert!(clipboard.is_image_available());
+    let pasted = clipboard.image().unwrap();
+    assert_eq!((pasted.width(), pasted.height()), (3, 2));
+
+    clipboard.clear();
+    assert!(!clipboard.is_text_available());
+    assert_eq!(clipboard.text(), None);
+}
@@ -0,0 +1,36 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_config_values() {
+    // Nothing is loaded or saved, so the user's config directory isn't touched.
+    let config = Config::new("ClearCoatTest");
+    assert_eq!(config.string("Group", "Missing"), None);
+
+    config.set_string("Group", "Name", "value");
+    config.set_int("Group", "Count", -3);
+    config.set_float("Other", "Scale", 1.5);
+    assert_eq!(config.string("Group", "Name"), Some("value".to_owned()));
+    assert_eq!(config.int("Group", "Count"), Some(-3));
+    assert_eq!(config.float("Other", "Scale"), Some(1.5));
+    assert_eq!(config.int("Group", "Name"), None);
+
+    let split = Split::with_children(&Text::new(), &Text::new());
+    split.set_ratio(0.25);
+    split.save_ratio(&config, "MainSplit");
+    split.set_ratio(0.5);
+    split.restore_ratio(&config, "MainSplit");
+    assert_eq!(split.ratio(), 0.25);
+    // Invalid saved ratios are ignored.
+    config.set_float("MainSplit", "Ratio", 7.0);
+    split.restore_ratio(&config, "MainSplit");
+    assert_eq!(split.ratio(), 0.25);
+}
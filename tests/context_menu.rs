@@ -0,0 +1,29 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+use clear_coat::common_attrs_cbs::*;
+
+#[test]
+fn test_context_menu() {
+    let menu = Menu::with_children(&[&Item::with_title("Copy"), &Item::with_title("Paste")]);
+    menu.open_event().add(|| {});
+    let token = menu.menu_close_event().add(|| {});
+    menu.menu_close_event().remove(token);
+
+    let canvas = Canvas::new();
+    let token = canvas.context_menu(&menu);
+    // The canvas keeps the menu alive after the caller's reference is dropped.
+    drop(menu);
+    canvas.button_event().remove(token);
+
+    // The position of a control isn't known until it is mapped.
+    let menu = Menu::with_children(&[&Item::with_title("Cut")]);
+    assert_eq!(menu.popup_at(&canvas, 0, 0), Err(()));
+}
@@ -0,0 +1,41 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+extern crate iup_sys;
+
+use clear_coat::*;
+use clear_coat::global;
+use iup_sys::*;
+
+fn set_default_font_str(font: &str) {
+    unsafe {
+        IupSetStrGlobal("DEFAULTFONT\0".as_ptr() as *const i8, font.as_ptr() as *const i8);
+    }
+}
+
+#[test]
+fn test_default_font_formats() {
+    // Make sure IUP is opened before setting a global.
+    let _ = global::screen_size();
+
+    // GTK reports fonts in Pango's format, which has no comma.
+    set_default_font_str("DejaVu Sans Bold Italic 10\0");
+    let mut expected = Font::new("DejaVu Sans", 10);
+    expected.bold = true;
+    expected.italic = true;
+    assert_eq!(global::default_font(), expected);
+
+    set_default_font_str("Sans 12px\0");
+    assert_eq!(global::default_font(), Font::new("Sans", -12));
+
+    // Styles in IUP's format are case insensitive, and unknown ones are ignored.
+    set_default_font_str("Courier, bold Condensed 9\0");
+    let mut expected = Font::new("Courier", 9);
+    expected.bold = true;
+    assert_eq!(global::default_font(), expected);
+}
@@ -0,0 +1,35 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use std::ptr;
+use clear_coat::*;
+
+#[test]
+fn test_dialog_attributes() {
+    let dialog = Dialog::new();
+    dialog.set_resizable(false).set_max_box(false).set_shrink(true);
+    assert!(!dialog.resizable());
+    assert!(!dialog.has_max_box());
+    assert!(dialog.shrink());
+    dialog.set_placement(Placement::Maximized);
+    assert_eq!(dialog.placement(), Placement::Maximized);
+    assert!(!dialog.is_modal());
+
+    // The dialog keeps its parent alive.
+    assert!(dialog.parent_dialog().is_none());
+    let parent = Dialog::new();
+    let parent_ih = parent.handle();
+    dialog.set_parent_dialog(Some(&parent));
+    drop(parent);
+    let parent = dialog.parent_dialog().expect("parent dialog was not set");
+    assert_eq!(parent.handle(), parent_ih);
+    assert!(parent.handle() != ptr::null_mut());
+    dialog.set_parent_dialog(None);
+    assert!(dialog.parent_dialog().is_none());
+}
@@ -0,0 +1,25 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_dialog_tray() {
+    let dialog = Dialog::new();
+    assert!(!dialog.tray());
+    dialog.set_tray_tip_balloon(true).set_tray_tip_balloon_title("Service").set_tray_tip("Running");
+    assert!(dialog.tray_tip_balloon());
+    assert_eq!(dialog.tray_tip(), "Running");
+
+    // The dialog keeps the menu alive after the caller's reference is dropped.
+    let menu = Menu::with_children(&[&Item::with_title("Quit")]);
+    dialog.set_tray_menu(Some(&menu));
+    drop(menu);
+    dialog.set_tray_menu(None);
+}
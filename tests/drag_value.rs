@@ -0,0 +1,37 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use std::rc::Rc;
+use clear_coat::*;
+use clear_coat::common_attrs_cbs::*;
+
+#[test]
+fn test_drag_value() {
+    let label = Label::new();
+    label.set_drag_source(&["ITEM"]).set_drag_source_move(true);
+    assert!(label.drag_source());
+    assert!(label.drag_source_move());
+    label.set_drop_target(&["ITEM", "TEXT"]);
+    assert!(label.drop_target());
+
+    // The value isn't copied, so an `Rc` comes back pointing at the same allocation.
+    let value = Rc::new(vec![1, 2, 3]);
+    let data = drag_value(value.clone());
+    assert_eq!(take_drag_value::<String>(&data), None);
+    let taken = take_drag_value::<Rc<Vec<i32>>>(&data).expect("value was not found");
+    assert!(Rc::ptr_eq(&taken, &value));
+    assert!(take_drag_value::<Rc<Vec<i32>>>(&data).is_none());
+
+    // Data that didn't come from `drag_value` is ignored, as is an ID that has been replaced.
+    assert!(take_drag_value::<i32>(b"some text").is_none());
+    let old_data = drag_value(1i32);
+    let new_data = drag_value(2i32);
+    assert!(take_drag_value::<i32>(&old_data).is_none());
+    assert_eq!(take_drag_value::<i32>(&new_data), Some(2));
+}
@@ -0,0 +1,62 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+extern crate iup_sys;
+
+use std::cell::{Cell, RefCell};
+use std::mem;
+use std::rc::Rc;
+use clear_coat::*;
+use clear_coat::common_attrs_cbs::*;
+use iup_sys::*;
+
+// Calls the dialog's DROPFILES_CB callback like IUP does for each dropped file.
+fn drop_file(dialog: &Dialog, filename: &str, num: i32) -> i32 {
+    unsafe {
+        let cb = IupGetCallback(dialog.handle(), "DROPFILES_CB\0".as_ptr() as *const i8);
+        let cb = mem::transmute::<Icallback, extern fn(*mut Ihandle, *const i8, i32, i32, i32) -> i32>(cb);
+        let filename = format!("{}\0", filename);
+        cb(dialog.handle(), filename.as_ptr() as *const i8, num, 10, 20)
+    }
+}
+
+#[test]
+fn test_drop_files() {
+    let dialog = Dialog::new();
+    let drops = Rc::new(RefCell::new(vec![]));
+    let drops2 = drops.clone();
+    let ignore = Rc::new(Cell::new(false));
+    let ignore2 = ignore.clone();
+    dialog.drop_files_event().add(move |args: &DropFilesArgs| {
+        assert_eq!((args.x, args.y), (10, 20));
+        drops2.borrow_mut().push((args.path.to_string_lossy().into_owned(), args.index, args.count));
+        if ignore2.get() { CallbackAction::Ignore } else { CallbackAction::Default }
+    });
+
+    // IUP delivers the files from the highest index down to 0.
+    drop_file(&dialog, "c.txt", 2);
+    drop_file(&dialog, "b.txt", 1);
+    drop_file(&dialog, "a.txt", 0);
+    assert_eq!(*drops.borrow(), vec![("c.txt".to_owned(), 2, 3),
+                                     ("b.txt".to_owned(), 1, 3),
+                                     ("a.txt".to_owned(), 0, 3)]);
+
+    // The count is forgotten after index 0, so the next drop has its own count.
+    drops.borrow_mut().clear();
+    drop_file(&dialog, "e.txt", 1);
+    drop_file(&dialog, "d.txt", 0);
+    assert_eq!(*drops.borrow(), vec![("e.txt".to_owned(), 1, 2), ("d.txt".to_owned(), 0, 2)]);
+
+    // Ignoring a file makes IUP skip the rest of the drop, so the count is forgotten then too.
+    drops.borrow_mut().clear();
+    ignore.set(true);
+    assert_eq!(drop_file(&dialog, "h.txt", 2), IUP_IGNORE);
+    ignore.set(false);
+    drop_file(&dialog, "i.txt", 0);
+    assert_eq!(*drops.borrow(), vec![("h.txt".to_owned(), 2, 3), ("i.txt".to_owned(), 0, 1)]);
+}
@@ -0,0 +1,32 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+use clear_coat::global::{self, Language};
+
+#[test]
+fn test_global_attributes() {
+    let (width, height) = global::screen_size();
+    assert!(width > 0 && height > 0);
+    let monitors = global::monitors_info();
+    assert!(!monitors.is_empty());
+    assert!(global::screen_dpi().unwrap() > 0.0);
+
+    let mut font = global::default_font();
+    font.bold = true;
+    font.size = 14;
+    global::set_default_font(&font);
+    assert_eq!(global::default_font(), font);
+
+    global::set_dlg_bg_color(Color::new(10, 20, 30));
+    assert_eq!(global::dlg_bg_color(), Color::new(10, 20, 30));
+
+    global::set_language(Language::Spanish);
+    assert_eq!(global::language(), Language::Spanish);
+}
@@ -0,0 +1,43 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+#![cfg(feature = "load-image")]
+
+extern crate clear_coat;
+
+use clear_coat::*;
+use clear_coat::common_attrs_cbs::*;
+
+// A 2x1 24-bit BMP with a red pixel and a blue pixel.
+const RED_BLUE_BMP: &'static [u8] = &[
+    b'B', b'M', 62, 0, 0, 0, 0, 0, 0, 0, 54, 0, 0, 0, // file header
+    40, 0, 0, 0, 2, 0, 0, 0, 1, 0, 0, 0, 1, 0, 24, 0, // info header
+    0, 0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 255, 255, 0, 0, 0, 0, // pixels as BGR, padded to four bytes
+];
+
+#[test]
+fn test_image_load() {
+    let image = Image::load_from_memory(RED_BLUE_BMP).expect("failed to load BMP");
+    assert_eq!(image.width(), 2);
+    assert_eq!(image.height(), 1);
+
+    match Image::load_from_memory(b"not an image") {
+        Err(ImageLoadError::UnknownFormat) => {},
+        _ => panic!("expected an unknown format error"),
+    }
+    match Image::load_from_memory(&RED_BLUE_BMP[..40]) {
+        Err(ImageLoadError::Decode(_)) => {},
+        _ => panic!("expected a decode error for a truncated BMP"),
+    }
+
+    // Test that a button keeps the image alive after the wrapper is dropped.
+    let button = Button::new();
+    button.set_image(Some(&image));
+    drop(image);
+    button.set_image(None);
+}
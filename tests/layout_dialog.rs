@@ -0,0 +1,27 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+use clear_coat::common_attrs_cbs::*;
+
+#[test]
+fn test_layout_dialog_keeps_references() {
+    let button = Button::new();
+    let dialog = Dialog::with_child(&button);
+    let layout = LayoutDialog::new(&dialog);
+    let properties = ElementPropertiesDialog::new(&button);
+    // The layout dialog holds a reference, so the dialog and its child aren't destroyed here.
+    drop(dialog);
+    button.set_title("Still alive");
+    assert_eq!(button.title(), "Still alive");
+
+    drop(button);
+    drop(properties);
+    drop(layout);
+}
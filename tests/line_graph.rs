@@ -0,0 +1,38 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_line_graph_series() {
+    let graph = LineGraph::new();
+    let a = graph.add_series(vec![DataPoint::new(2.0, 5.0), DataPoint::new(0.0, -1.0), DataPoint::new(1.0, 3.0)]);
+    let b = graph.add_series(vec![DataPoint::new(4.0, 0.0)]);
+    assert_eq!((a, b), (0, 1));
+    assert_eq!(graph.series_count(), 2);
+
+    // Points are sorted by x.
+    let xs: Vec<f64> = graph.series_points(a).iter().map(|pt| pt.x).collect();
+    assert_eq!(xs, vec![0.0, 1.0, 2.0]);
+
+    graph.autoscale_all();
+    assert_eq!(graph.x_range(), 0.0..4.0);
+    assert_eq!(graph.y_range(), -1.0..5.0);
+
+    // Only points within the x range are used to scale the y range, and a single point is
+    // widened so that it can be drawn.
+    graph.set_x_range(0.5..1.5);
+    graph.autoscale_y();
+    let y_range = graph.y_range();
+    assert!(y_range.start < 3.0 && y_range.end > 3.0 && y_range.end - y_range.start < 1.0);
+
+    graph.remove_series(a);
+    assert_eq!(graph.series_count(), 1);
+    assert_eq!(graph.series_points(0), vec![DataPoint::new(4.0, 0.0)]);
+}
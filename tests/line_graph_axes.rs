@@ -0,0 +1,44 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_line_graph_axes() {
+    let graph = LineGraph::new();
+    let temperature = graph.add_series(vec![DataPoint::new(0.0, 20.0), DataPoint::new(10.0, 30.0)]);
+    let pressure = graph.add_series(vec![DataPoint::new(0.0, 1000.0), DataPoint::new(10.0, 1010.0)]);
+    let right = graph.add_y_axis(YAxisSide::Right);
+    assert_eq!(right, 1);
+    assert_eq!(graph.y_axis_count(), 2);
+    graph.set_series_axis(pressure, right);
+    assert_eq!(graph.series_axis(pressure), right);
+    assert_eq!(graph.series_axis(temperature), 0);
+
+    // Each axis is scaled to its own series.
+    graph.autoscale_all();
+    assert_eq!(graph.y_axis_range(0), 20.0..30.0);
+    assert_eq!(graph.y_axis_range(right), 1000.0..1010.0);
+
+    // Hidden series aren't used for autoscaling.
+    graph.set_series_axis(pressure, 0);
+    graph.set_series_visible(pressure, false);
+    graph.autoscale_all();
+    assert_eq!(graph.y_range(), 20.0..30.0);
+    graph.set_series_visible(pressure, true);
+    graph.autoscale_all();
+    assert_eq!(graph.y_range(), 20.0..1010.0);
+
+    graph.set_series_line_style(temperature, LineStyle::Dashed);
+    graph.set_series_width(temperature, 2);
+    assert_eq!(graph.series_line_style(temperature), LineStyle::Dashed);
+    assert_eq!(graph.series_width(temperature), 2);
+    graph.set_legend_visible(true);
+    assert!(graph.legend_visible());
+}
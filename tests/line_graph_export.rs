@@ -0,0 +1,43 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_line_graph_export() {
+    let graph = LineGraph::new();
+    let a = graph.add_series(vec![DataPoint::new(0.0, 1.0), DataPoint::new(2.0, 3.0)]);
+    graph.add_series(vec![DataPoint::new(1.0, 0.5), DataPoint::new(2.0, -1.0)]);
+    graph.set_series_name(a, "Speed, m/s");
+
+    let mut csv = vec![];
+    graph.write_csv(&mut csv, CsvLayout::Aligned).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(),
+               "x,\"Speed, m/s\",Series 2\r\n0,1,\r\n1,,0.5\r\n2,3,-1\r\n");
+
+    let mut csv = vec![];
+    graph.write_csv(&mut csv, CsvLayout::Long).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(),
+               "series,x,y\r\n\"Speed, m/s\",0,1\r\n\"Speed, m/s\",2,3\r\nSeries 2,1,0.5\r\nSeries 2,2,-1\r\n");
+
+    // A point without an x value is ignored instead of stopping the merge of the series.
+    graph.append_point(a, DataPoint::new(std::f64::NAN, 5.0));
+    graph.append_point(a, DataPoint::new(3.0, 4.0));
+    let mut csv = vec![];
+    graph.write_csv(&mut csv, CsvLayout::Aligned).unwrap();
+    assert_eq!(String::from_utf8(csv).unwrap(),
+               "x,\"Speed, m/s\",Series 2\r\n0,1,\r\n1,,0.5\r\n2,3,-1\r\n3,4,\r\n");
+
+    // Rendering doesn't depend on the canvas being shown.
+    graph.autoscale_all();
+    let pixels = graph.render_rgba(320, 240);
+    assert_eq!(pixels.len(), 320 * 240 * 4);
+    // The top left corner is outside the plot, so it is the background color.
+    assert_eq!(&pixels[0..4], &[255, 255, 255, 255]);
+}
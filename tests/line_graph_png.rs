@@ -0,0 +1,29 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+#![cfg(feature = "save-image")]
+
+extern crate clear_coat;
+extern crate png;
+
+use clear_coat::*;
+
+#[test]
+fn test_line_graph_png() {
+    let graph = LineGraph::new();
+    graph.add_series(vec![DataPoint::new(0.0, 1.0), DataPoint::new(2.0, 3.0)]);
+    graph.autoscale_all();
+
+    let mut data = vec![];
+    graph.write_png(&mut data, 64, 48).unwrap();
+    let (info, mut reader) = png::Decoder::new(&data[..]).read_info().unwrap();
+    assert_eq!((info.width, info.height), (64, 48));
+    assert_eq!(info.color_type, png::ColorType::RGBA);
+    let mut pixels = vec![0; info.buffer_size()];
+    reader.next_frame(&mut pixels).unwrap();
+    assert_eq!(pixels, graph.render_rgba(64, 48));
+}
@@ -0,0 +1,36 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_line_graph_streaming() {
+    let graph = LineGraph::new();
+    graph.set_build_pyramids_in_background(false);
+    let series = graph.add_series(vec![]);
+    graph.set_series_capacity(series, Some(5000));
+    graph.set_x_scroll_window(Some(100.0));
+
+    for i in 0..10000 {
+        graph.append_point(series, DataPoint::new(i as f64, (i % 7) as f64));
+    }
+    let points = graph.series_points(series);
+    assert_eq!(points.len(), 5000);
+    assert_eq!(points[0].x, 5000.0);
+    assert_eq!(graph.x_range(), 9899.0..9999.0);
+
+    // Points appended out of order are kept sorted.
+    graph.append_points(series, &[DataPoint::new(9000.5, 1.0), DataPoint::new(10000.0, 2.0)]);
+    let points = graph.series_points(series);
+    assert_eq!(points.len(), 5000);
+    assert!(points.windows(2).all(|pair| pair[0].x <= pair[1].x));
+    assert!(points.iter().any(|pt| pt.x == 9000.5));
+    assert_eq!(points.last().unwrap().x, 10000.0);
+    assert_eq!(graph.x_range(), 9900.0..10000.0);
+}
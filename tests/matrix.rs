@@ -0,0 +1,36 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+#![cfg(feature = "iup-controls")]
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_matrix_zero_based_cells() {
+    let matrix = Matrix::new();
+    matrix.set_num_lines(3).set_num_columns(2);
+    matrix.set_column_title(0, "Name");
+    matrix.set_cell(0, 0, "first");
+    matrix.set_cell(2, 1, "last");
+    assert_eq!(matrix.column_title(0), "Name");
+    assert_eq!(matrix.cell(0, 0), "first");
+    assert_eq!(matrix.cell(2, 1), "last");
+
+    // Inserting a line at the start shifts the existing cells down.
+    matrix.insert_lines(0, 1);
+    assert_eq!(matrix.num_lines(), 4);
+    assert_eq!(matrix.cell(0, 0), "");
+    assert_eq!(matrix.cell(1, 0), "first");
+
+    matrix.remove_lines(0, 2);
+    assert_eq!(matrix.num_lines(), 2);
+    assert_eq!(matrix.cell(1, 1), "last");
+
+    matrix.set_column_alignment(1, HAlignment::Right);
+    assert_eq!(matrix.column_alignment(1), Some(HAlignment::Right));
+}
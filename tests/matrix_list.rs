@@ -0,0 +1,33 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+#![cfg(feature = "iup-controls")]
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_matrix_list_items() {
+    let list = MatrixList::new();
+    list.set_show_checkboxes(true);
+    list.set_items(&["a", "c"]);
+    list.insert_item(1, "b");
+    assert_eq!(list.count(), 3);
+    assert_eq!(list.item(0), "a");
+    assert_eq!(list.item(1), "b");
+    assert_eq!(list.item(2), "c");
+
+    list.set_checked(2, true);
+    assert_eq!(list.checked_items(), vec![2]);
+
+    list.remove_item(0);
+    assert_eq!(list.count(), 2);
+    assert_eq!(list.item(0), "b");
+
+    list.clear();
+    assert_eq!(list.count(), 0);
+}
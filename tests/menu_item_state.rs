@@ -0,0 +1,34 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+use clear_coat::common_attrs_cbs::*;
+
+#[test]
+fn test_menu_item_state() {
+    let show_toolbar = Item::with_title("Show Toolbar");
+    assert!(!show_toolbar.checked());
+    show_toolbar.set_auto_toggle(true).set_checked(true);
+    assert!(show_toolbar.auto_toggle());
+    assert!(show_toolbar.checked());
+    show_toolbar.set_checked(false);
+    assert!(!show_toolbar.checked());
+
+    let image = Image::with_rgba(1, 1, &[0, 0, 0, 255]);
+    show_toolbar.set_image(Some(&image)).set_image_pressed(Some(&image));
+    show_toolbar.set_image(None).set_image_pressed(None);
+
+    let small = Item::with_title("Small");
+    let large = Item::with_title("Large");
+    let sizes = Menu::with_children(&[&small, &large]);
+    sizes.set_radio(true);
+    assert!(sizes.radio());
+    large.set_checked(true);
+    assert!(large.checked());
+}
@@ -0,0 +1,82 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+extern crate iup_sys;
+
+use std::cell::Cell;
+use std::rc::Rc;
+use clear_coat::*;
+use clear_coat::common_attrs_cbs::*;
+use iup_sys::*;
+
+#[test]
+fn test_menu_mutation() {
+    let counter = Rc::new(Cell::new(0));
+    let dialog = Dialog::with_child(&Fill::new());
+    let menu = Menu::new();
+    dialog.set_menu(Some(&menu));
+    dialog.show_event().add(|_| CallbackAction::Close);
+    dialog.show_xy(ScreenPosition::Center, ScreenPosition::Center).expect("could not show dialog");
+    main_loop();
+
+    // Items added while the menu is shown are mapped.
+    let open = Item::with_title("Open");
+    let a_txt = Item::with_title("a.txt");
+    let inner = Menu::with_children(&[&a_txt]);
+    let recent = Submenu::with_title_and_menu("Recent", &inner);
+    let separator = Separator::new();
+    menu.append_item(&open).insert_item(0, &recent).append_item(&separator);
+    assert_eq!(menu.child_count(), 3);
+    unsafe { assert!(!IupGetAttribute(open.handle(), "WID\0".as_ptr() as *const i8).is_null()); }
+
+    menu.move_item(0, 2);
+    unsafe { assert_eq!(IupGetChild(menu.handle(), 0), open.handle()); }
+    unsafe { assert_eq!(IupGetChild(menu.handle(), 2), recent.handle()); }
+    // Moving a mapped item keeps its check state.
+    open.set_auto_toggle(true).set_checked(true);
+    menu.move_item(0, 1).move_item(1, 0);
+    unsafe { assert_eq!(IupGetChild(menu.handle(), 0), open.handle()); }
+    assert!(open.checked());
+    // Moving a mapped submenu keeps the check state of the items inside it.
+    a_txt.set_auto_toggle(true).set_checked(true);
+    menu.move_item(2, 0).move_item(0, 2);
+    unsafe { assert_eq!(IupGetChild(menu.handle(), 2), recent.handle()); }
+    assert!(a_txt.checked());
+    drop(a_txt);
+
+    // A removed submenu isn't destroyed while a wrapper references it and can be added again.
+    let counter2 = counter.clone();
+    recent.destroy_event().add(move || counter2.set(counter2.get() + 1));
+    let counter2 = counter.clone();
+    inner.destroy_event().add(move || counter2.set(counter2.get() + 10));
+    menu.remove_item(2);
+    assert_eq!(counter.get(), 0);
+    menu.append_item(&recent);
+    assert_eq!(menu.child_count(), 3);
+
+    // Without the submenu's wrapper, removing it destroys it, but the menu inside it is kept
+    // alive by its wrapper.
+    drop(recent);
+    assert_eq!(counter.get(), 0);
+    menu.remove_item(2);
+    assert_eq!(counter.get(), 1);
+    assert_eq!(inner.child_count(), 1);
+    drop(inner);
+    assert_eq!(counter.get(), 11);
+
+    // Removing an item that only the menu references destroys it.
+    let counter2 = counter.clone();
+    separator.destroy_event().add(move || counter2.set(counter2.get() + 100));
+    drop(separator);
+    menu.remove_item(1);
+    assert_eq!(counter.get(), 111);
+    assert_eq!(menu.child_count(), 1);
+    drop(open);
+    drop(menu);
+    drop(dialog);
+}
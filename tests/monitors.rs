@@ -0,0 +1,33 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+use clear_coat::global::{self, Monitor};
+
+#[test]
+fn test_clamp_to_monitors() {
+    let monitors = [
+        Monitor { rect: Rect::new(0, 0, 1920, 1080), primary: true, dpi: 96.0 },
+        Monitor { rect: Rect::new(1920, 0, 1280, 1024), primary: false, dpi: 96.0 },
+    ];
+    // Fully visible rectangles aren't moved.
+    let r = Rect::new(100, 100, 400, 300);
+    assert_eq!(global::clamp_to_monitors(&monitors, r), r);
+    // A rectangle hanging off the bottom right of the second monitor is moved onto it.
+    assert_eq!(global::clamp_to_monitors(&monitors, Rect::new(3000, 900, 400, 300)),
+               Rect::new(2800, 724, 400, 300));
+    // A rectangle on a disconnected monitor is moved to the closest one.
+    assert_eq!(global::clamp_to_monitors(&monitors, Rect::new(-2000, 50, 400, 300)),
+               Rect::new(0, 50, 400, 300));
+    // A rectangle larger than the monitor keeps its top left corner visible.
+    assert_eq!(global::clamp_to_monitors(&monitors, Rect::new(-50, -50, 2000, 1200)),
+               Rect::new(0, 0, 2000, 1200));
+
+    assert_eq!(global::monitors().iter().filter(|m| m.primary).count(), 1);
+}
@@ -0,0 +1,56 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+extern crate iup_sys;
+
+use std::ffi::CStr;
+use std::path::{Path, PathBuf};
+use clear_coat::*;
+use iup_sys::*;
+
+fn item_title(menu: &Menu, index: i32) -> String {
+    unsafe {
+        let title = IupGetAttribute(IupGetChild(menu.handle(), index), "TITLE\0".as_ptr() as *const i8);
+        CStr::from_ptr(title).to_string_lossy().into_owned()
+    }
+}
+
+#[test]
+fn test_recent_files() {
+    let menu = Menu::new();
+    let recent = RecentFiles::new(&menu, 3);
+    // An inactive placeholder item is shown when the list is empty.
+    assert_eq!(menu.child_count(), 1);
+
+    recent.add(Path::new("a.txt"));
+    recent.add(Path::new("b.txt"));
+    recent.add(Path::new("c.txt"));
+    recent.add(Path::new("a.txt"));
+    recent.add(Path::new("d.txt"));
+    assert_eq!(recent.paths(), vec![PathBuf::from("d.txt"), PathBuf::from("a.txt"), PathBuf::from("c.txt")]);
+    assert_eq!(menu.child_count(), 3);
+
+    assert_eq!(item_title(&menu, 0), "&1 d.txt");
+
+    recent.remove(Path::new("a.txt"));
+    assert_eq!(menu.child_count(), 2);
+
+    // An "&" in a path is shown as is instead of marking a mnemonic.
+    recent.add(Path::new("Tom & Jerry.txt"));
+    assert_eq!(item_title(&menu, 0), "&1 Tom && Jerry.txt");
+    recent.remove(Path::new("Tom & Jerry.txt"));
+
+    let config = Config::new("ClearCoatTest");
+    recent.save(&config, "Recent");
+    let other = RecentFiles::new(&Menu::new(), 3);
+    other.load(&config, "Recent");
+    assert_eq!(other.paths(), recent.paths());
+
+    recent.clear();
+    assert_eq!(menu.child_count(), 1);
+}
@@ -0,0 +1,63 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+extern crate iup_sys;
+
+use std::cell::Cell;
+use std::mem;
+use std::rc::Rc;
+use clear_coat::*;
+use clear_coat::common_attrs_cbs::*;
+use iup_sys::*;
+
+// Calls the dialog's K_ANY callback like IUP does when a key is pressed.
+fn press(dialog: &Dialog, code: u32) -> i32 {
+    unsafe {
+        let cb = IupGetCallback(dialog.handle(), "K_ANY\0".as_ptr() as *const i8);
+        let cb = mem::transmute::<Icallback, extern fn(*mut Ihandle, i32) -> i32>(cb);
+        cb(dialog.handle(), code as i32)
+    }
+}
+
+#[test]
+fn test_shortcuts() {
+    const K_CTRL_S: u32 = 0x20000000 | 'S' as u32;
+    const K_F5: u32 = 0xFFC2;
+
+    let ctrl_s = KeyChord::new(Key::Char('s')).ctrl();
+    assert_eq!(ctrl_s.to_string(), "Ctrl+S");
+    assert_eq!(KeyChord::new(Key::Home).ctrl().shift().to_string(), "Ctrl+Shift+Home");
+
+    let dialog = Dialog::new();
+    let save = Item::with_title("&Save\tCtrl+Q");
+    let saved = Rc::new(Cell::new(0));
+    let saved2 = saved.clone();
+    save.action_event().add(move || saved2.set(saved2.get() + 1));
+    dialog.shortcuts().bind_item(ctrl_s, &save);
+    assert_eq!(save.title(), "&Save\tCtrl+S");
+    assert!(dialog.shortcuts().is_bound(KeyChord::new(Key::Char('S')).ctrl()));
+
+    assert_eq!(press(&dialog, K_CTRL_S), IUP_IGNORE);
+    assert_eq!(saved.get(), 1);
+    save.set_active(false);
+    press(&dialog, K_CTRL_S);
+    assert_eq!(saved.get(), 1);
+    assert_eq!(press(&dialog, 's' as u32), IUP_DEFAULT);
+
+    let refreshed = Rc::new(Cell::new(false));
+    let refreshed2 = refreshed.clone();
+    dialog.shortcuts().bind(KeyChord::new(Key::F(5)), move || refreshed2.set(true));
+    press(&dialog, K_F5);
+    assert!(refreshed.get());
+
+    // Binding the chord again replaces the item, so the item's title loses the chord.
+    dialog.shortcuts().bind(ctrl_s, || {});
+    assert_eq!(save.title(), "&Save");
+    dialog.shortcuts().unbind(ctrl_s);
+    assert!(!dialog.shortcuts().is_bound(ctrl_s));
+}
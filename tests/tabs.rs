@@ -0,0 +1,36 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+
+use clear_coat::*;
+
+#[test]
+fn test_tabs_attributes() {
+    let tabs = Tabs::new();
+    let first = Text::new();
+    let second = Text::new();
+    tabs.append_tabs(&[
+        TabInfo::new(&first).title("First"),
+        TabInfo::new(&second).title("Second"),
+    ]);
+
+    // Indexes are zero-based.
+    assert_eq!(tabs.tab_title(0), "First");
+    assert_eq!(tabs.tab_title(1), "Second");
+    assert_eq!(tabs.tab_index(&first), Some(0));
+    assert_eq!(tabs.tab_index(&second), Some(1));
+    assert_eq!(tabs.tab_index(&Text::new()), None);
+
+    tabs.set_current_tab_control(&second);
+    assert_eq!(tabs.current_tab_control(), Some(second.handle()));
+
+    tabs.set_tab_position(TabPosition::Left);
+    assert_eq!(tabs.tab_position(), TabPosition::Left);
+    tabs.set_show_close(true);
+    assert!(tabs.shows_close());
+}
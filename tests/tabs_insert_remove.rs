@@ -0,0 +1,52 @@
+/* Copyright 2016 Jordan Miner
+ *
+ * Licensed under the MIT license <LICENSE or
+ * http://opensource.org/licenses/MIT>. This file may not be copied,
+ * modified, or distributed except according to those terms.
+ */
+
+extern crate clear_coat;
+extern crate iup_sys;
+
+use std::ptr;
+use clear_coat::*;
+use iup_sys::*;
+
+#[test]
+fn test_tabs_insert_remove() {
+    let tabs = Tabs::new();
+    let a = Text::new();
+    let b = Text::new();
+    let c = Text::new();
+    tabs.append_tabs(&[
+        TabInfo::new(&a).title("A"),
+        TabInfo::new(&c).title("C").tip("Third"),
+    ]);
+    tabs.insert_tab(1, TabInfo::new(&b).title("B"));
+    assert_eq!(tabs.child_count(), 3);
+    assert_eq!(tabs.tab_index(&b), Some(1));
+    assert_eq!(tabs.tab_title(2), "C");
+
+    // The title moves with the tab.
+    tabs.move_tab(0, 2);
+    assert_eq!(tabs.tab_index(&a), Some(2));
+    assert_eq!(tabs.tab_index(&b), Some(0));
+    assert_eq!(tabs.tab_title(2), "A");
+    tabs.move_tab(2, 0);
+    assert_eq!(tabs.tab_index(&a), Some(0));
+
+    // A removed tab's control survives while a wrapper references it and can be added again.
+    tabs.remove_tab(1);
+    assert_eq!(tabs.child_count(), 2);
+    assert!(b.handle() != ptr::null_mut());
+    unsafe { assert!(IupGetParent(b.handle()).is_null()); }
+    tabs.insert_tab(2, TabInfo::new(&b).title("B again"));
+    assert_eq!(tabs.tab_index(&b), Some(2));
+    assert_eq!(tabs.tab_title(2), "B again");
+
+    // Without a wrapper, removing a tab destroys its control.
+    tabs.append_tabs(&[TabInfo::new(&Text::new()).title("Temporary")]);
+    assert_eq!(tabs.child_count(), 4);
+    tabs.remove_tab(3);
+    assert_eq!(tabs.child_count(), 3);
+}